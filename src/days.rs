@@ -11,6 +11,9 @@ use crate::days::day08::Day8Solver;
 use crate::days::day09::Day9Solver;
 use crate::days::day10::Day10Solver;
 use crate::days::day11::Day11Solver;
+use crate::utils::memo::MemoReport;
+use crate::utils::parse::ParseError;
+use crate::utils::visualize::{Visualize, VisualizeError};
 
 mod day01;
 mod day02;
@@ -35,10 +38,28 @@ pub enum DayError {
     #[error("Received invalid input for day: {0}")]
     InvalidInputError(String),
 
+    #[error("The solution for this day does not support visualization.")]
+    VisualizationNotSupported,
+
+    #[error("The solution for this day does not support reports.")]
+    ReportNotSupported,
+
     #[error("Unknown error from day solution: '{0}'")]
     Unknown(String)
 }
 
+impl From<VisualizeError> for DayError {
+    fn from(value: VisualizeError) -> Self {
+        DayError::Unknown(value.to_string())
+    }
+}
+
+impl From<ParseError> for DayError {
+    fn from(value: ParseError) -> Self {
+        DayError::InvalidInputError(value.to_string())
+    }
+}
+
 pub struct Day(u8);
 
 impl TryFrom<u8> for Day {
@@ -55,6 +76,34 @@ impl TryFrom<u8> for Day {
 pub trait DaySolver {
     fn solve_part1(&self, input: &str) -> Result<String, DayError>;
     fn solve_part2(&self, input: &str) -> Result<String, DayError>;
+
+    /// Solves part 1, recording the statistics of the memos it used into `memos`.
+    fn solve_part1_with_memos(
+        &self,
+        input: &str,
+        _memos: &mut MemoReport,
+    ) -> Result<String, DayError> {
+        self.solve_part1(input)
+    }
+
+    /// Solves part 2, recording the statistics of the memos it used into `memos`.
+    fn solve_part2_with_memos(
+        &self,
+        input: &str,
+        _memos: &mut MemoReport,
+    ) -> Result<String, DayError> {
+        self.solve_part2(input)
+    }
+
+    /// Emits the intermediate states of a simulation day as frames.
+    fn visualize(&self, _input: &str, _visualizer: &mut dyn Visualize) -> Result<(), DayError> {
+        Err(DayError::VisualizationNotSupported)
+    }
+
+    /// Emits the structured results of the solution drawn onto the input as frames.
+    fn report(&self, _input: &str, _visualizer: &mut dyn Visualize) -> Result<(), DayError> {
+        Err(DayError::ReportNotSupported)
+    }
 }
 
 impl TryFrom<Day> for Box<dyn DaySolver> {
@@ -1,39 +1,22 @@
 use crate::days::{DayError, DaySolver};
-use std::collections::HashMap;
-use std::num::ParseIntError;
+use crate::utils::counter::Counter;
+use crate::utils::parse::{lines, pair, Span};
 
 pub struct Day1Solver;
 
 fn parse_input_to_lists(input: &str) -> Result<(Vec<i32>, Vec<i32>), DayError> {
-    Ok(input
-        .lines()
-        .filter_map(|line| line.split_once("   "))
-        .map(|numbers_as_string| -> Result<(i32, i32), ParseIntError> {
-            Ok((numbers_as_string.0.parse()?, numbers_as_string.1.parse()?))
-        })
-        .collect::<Result<Vec<_>, _>>()
-        .map_err(|_| {
-            DayError::InvalidInputError(
-                "Cannot parse the given input into number list".to_string(),
-            )
-        })?
-        .into_iter()
-        .unzip())
-}
+    let pairs: Vec<(i32, i32)> =
+        lines(Span::new(input), |line| pair(line, "   ", Span::parse, Span::parse))?;
 
-fn count_occurrences(list: &Vec<i32>) -> HashMap<i32, i32> {
-    list.iter().fold(HashMap::new(), |mut map, &list_entry| {
-        map.entry(list_entry)
-            .and_modify(|frequency| *frequency += 1)
-            .or_insert(1);
-        map
-    })
+    Ok(pairs.into_iter().unzip())
 }
 
-fn calculate_similarity(number: &i32, occurrences: &HashMap<i32, i32>) -> i32 {
-    let number_of_occurrences = occurrences.get(number).unwrap_or(&0);
+fn calculate_similarity(number: &i32, occurrences: &Counter<i32>) -> Result<i64, DayError> {
+    let number_of_occurrences = i64::try_from(occurrences.get(number)).map_err(|_| {
+        DayError::InvalidInputError(format!("{} occurs too often in the right list.", number))
+    })?;
 
-    number * number_of_occurrences
+    Ok(i64::from(*number) * number_of_occurrences)
 }
 
 impl DaySolver for Day1Solver {
@@ -60,11 +43,11 @@ impl DaySolver for Day1Solver {
 
     fn solve_part2(&self, input: &str) -> Result<String, DayError> {
         let (left_list, right_list) = parse_input_to_lists(input)?;
-        let occurrences = count_occurrences(&right_list);
-        let similarities: i32 = left_list
+        let occurrences: Counter<i32> = right_list.into_iter().collect();
+        let similarities: i64 = left_list
             .iter()
             .map(|entry| calculate_similarity(entry, &occurrences))
-            .sum();
+            .sum::<Result<_, _>>()?;
 
         Ok(similarities.to_string())
     }
@@ -93,4 +76,12 @@ mod tests {
         let solution = Day1Solver {}.solve_part2(get_example_input()).unwrap();
         assert_eq!(solution, "31")
     }
+
+    #[test]
+    fn test_similarity_beyond_i32() {
+        let solution = Day1Solver {}
+            .solve_part2("2000000000   2000000000\n2000000000   2000000000")
+            .unwrap();
+        assert_eq!(solution, "8000000000")
+    }
 }
@@ -55,7 +55,7 @@ fn is_safe_difference(level1: &i32, level2: &i32) -> bool {
     (level1 - level2).abs() <= 3
 }
 
-fn get_report_status(levels: &Vec<i32>) -> Result<ReportStatus, ReportError> {
+fn get_report_status(levels: &[i32]) -> Result<ReportStatus, ReportError> {
     if levels.len() < 2 {
         return Err(LevelInputError(
             "Levels must contain at least two entries".to_string(),
@@ -74,7 +74,7 @@ fn get_report_status(levels: &Vec<i32>) -> Result<ReportStatus, ReportError> {
             let current_direction = get_direction_for_levels(&window[0], &window[1]);
 
             if current_direction != prev_direction || !is_safe_difference(&window[0], &window[1]) {
-                return Err(UnsafeLevelTransition(window[0].clone(), window[1].clone()));
+                return Err(UnsafeLevelTransition(window[0], window[1]));
             }
 
             Ok(current_direction)
@@ -83,7 +83,7 @@ fn get_report_status(levels: &Vec<i32>) -> Result<ReportStatus, ReportError> {
         .or(Ok(Unsafe))
 }
 
-fn to_report(levels: &Vec<i32>) -> Result<Report, ReportError> {
+fn to_report(levels: &[i32]) -> Result<Report, ReportError> {
     Ok(Report {
         status: get_report_status(levels)?,
     })
@@ -108,7 +108,7 @@ impl DaySolver for Day2Solver {
         let reports = parse_input(input)
             .map_err(Into::<DayError>::into)?
             .iter()
-            .map(|levels| -> Result<Report, ReportError> { Ok(to_report(levels)?) })
+            .map(|levels| to_report(levels))
             .collect::<Result<Vec<Report>, ReportError>>()
             .map_err(Into::<DayError>::into)?
             .into_iter()
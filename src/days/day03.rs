@@ -1,89 +1,240 @@
-use crate::days::day03::InstructionStatus::{Disabled, Enabled};
 use crate::days::{DayError, DaySolver};
-use regex::Regex;
-use std::sync::LazyLock;
+use crate::utils::visualize::{Frame, Visualize};
+use std::fmt::{Display, Formatter};
+use thiserror::Error;
 
 pub struct Day3Solver;
 
-#[derive(Debug, PartialEq)]
-struct MultiplicationInstruction(i32, i32);
+#[derive(Debug, Error, PartialEq)]
+enum ProgramError {
+    #[error("Operand '{operand}' at position {position} does not fit into 64 bits")]
+    OperandOutOfRange { position: usize, operand: String },
+    #[error("Result of the instruction at position {position} overflows")]
+    Overflow { position: usize },
+}
+
+impl From<ProgramError> for DayError {
+    fn from(value: ProgramError) -> Self {
+        DayError::InvalidInputError(value.to_string())
+    }
+}
+
+/// Instructions of the corrupted memory. A new one needs a `parse` arm for its syntax
+/// and an `execute` arm for its effect.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Instruction {
+    Mul(i64, i64),
+    Do,
+    Dont,
+}
+
+impl Instruction {
+    /// Instruction at the very start of `memory` along with its length in bytes.
+    fn parse(memory: &str, position: usize) -> Result<Option<(Instruction, usize)>, ProgramError> {
+        if memory.starts_with("do()") {
+            return Ok(Some((Instruction::Do, "do()".len())));
+        }
+        if memory.starts_with("don't()") {
+            return Ok(Some((Instruction::Dont, "don't()".len())));
+        }
+
+        let Some(arguments) = memory.strip_prefix("mul(") else {
+            return Ok(None);
+        };
+        let left_length = digit_count(arguments);
+        let Some(rest) = arguments[left_length..].strip_prefix(",") else {
+            return Ok(None);
+        };
+        let right_length = digit_count(rest);
+        if left_length == 0 || right_length == 0 || !rest[right_length..].starts_with(")") {
+            return Ok(None);
+        }
+
+        let operand = |digits: &str| {
+            digits.parse().map_err(|_| ProgramError::OperandOutOfRange {
+                position,
+                operand: digits.to_string(),
+            })
+        };
+        let instruction = Instruction::Mul(
+            operand(&arguments[..left_length])?,
+            operand(&rest[..right_length])?,
+        );
+
+        Ok(Some((
+            instruction,
+            "mul(,)".len() + left_length + right_length,
+        )))
+    }
+}
+
+impl Display for Instruction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Instruction::Mul(left, right) => write!(f, "mul({left},{right})"),
+            Instruction::Do => write!(f, "do()"),
+            Instruction::Dont => write!(f, "don't()"),
+        }
+    }
+}
+
+fn digit_count(text: &str) -> usize {
+    text.bytes().take_while(u8::is_ascii_digit).count()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Token {
+    position: usize,
+    instruction: Instruction,
+}
+
+/// Valid instructions in the memory, skipping the garbage between them.
+fn tokenize(memory: &str) -> Result<Vec<Token>, ProgramError> {
+    let mut tokens = vec![];
+    let mut position = 0;
+
+    while position < memory.len() {
+        match Instruction::parse(&memory[position..], position)? {
+            Some((instruction, length)) => {
+                tokens.push(Token {
+                    position,
+                    instruction,
+                });
+                position += length;
+            }
+            None => position += memory[position..].chars().next().map_or(1, char::len_utf8),
+        }
+    }
+
+    Ok(tokens)
+}
 
-#[derive(PartialEq, Debug)]
-enum InstructionStatus {
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Outcome {
+    Executed { result: i64 },
+    Skipped { disabled_at: Option<usize> },
     Enabled,
     Disabled,
+    Ignored,
 }
 
-impl MultiplicationInstruction {
-    pub fn execute(&self) -> i32 {
-        self.0 * self.1
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct TraceEntry {
+    token: Token,
+    outcome: Outcome,
+}
+
+impl Display for TraceEntry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "@{} {}: ", self.token.position, self.token.instruction)?;
+        match self.outcome {
+            Outcome::Executed { result } => write!(f, "executed, result {result}"),
+            Outcome::Skipped {
+                disabled_at: Some(position),
+            } => write!(f, "skipped, disabled by don't() @{position}"),
+            Outcome::Skipped { disabled_at: None } => write!(f, "skipped, disabled"),
+            Outcome::Enabled => write!(f, "enables mul"),
+            Outcome::Disabled => write!(f, "disables mul"),
+            Outcome::Ignored => write!(f, "ignored, conditionals are off"),
+        }
     }
 }
 
-static MULTIPLICATION_INSTRUCTION_REGEX: LazyLock<Regex> =
-    LazyLock::new(|| Regex::new(r"mul\((?<left_number>\d*),(?<right_number>\d*)\)").unwrap());
-static PART2_REGEX: LazyLock<Regex> =
-    LazyLock::new(|| Regex::new(r"mul\((\d+),(\d+)\)|do\(\)|don't\(\)").unwrap());
-
-fn parse_line_to_multiplication_instructions<'a>(
-    input: &'a str,
-) -> (impl Iterator<Item = MultiplicationInstruction> + 'a) {
-    MULTIPLICATION_INSTRUCTION_REGEX
-        .captures_iter(input)
-        .map(|caps| {
-            let (_, [left_number, right_number]) = caps.extract();
-            MultiplicationInstruction(left_number.parse().unwrap(), right_number.parse().unwrap())
-        })
+/// Execution state of the memory. Without conditionals `do()` and `don't()` have no effect.
+#[derive(Debug)]
+struct Machine {
+    conditionals: bool,
+    disabled_at: Option<usize>,
+    sum: i64,
+    trace: Option<Vec<TraceEntry>>,
 }
 
-fn part2_parse_line_to_multiplication_instructions<'a>(
-    input: &'a str,
-) -> (impl Iterator<Item = MultiplicationInstruction> + 'a) {
-    let mut program_status = Enabled;
-    PART2_REGEX.captures_iter(input).filter_map(move |caps| {
-        if let Some(line) = caps.get(0).map(|m| m.as_str()) {
-            return match line {
-                "do()" => {
-                    program_status = Enabled;
-                    None
-                }
-                "don't()" => {
-                    program_status = Disabled;
-                    None
-                }
-                _ => match program_status {
-                    Enabled => {
-                        if let (Some(left_number), Some(right_number)) = (caps.get(1), caps.get(2))
-                        {
-                            return Some(MultiplicationInstruction(
-                                left_number.as_str().parse().unwrap(),
-                                right_number.as_str().parse().unwrap(),
-                            ));
-                        }
-
-                        None
-                    }
-                    Disabled => None,
-                },
-            };
+impl Machine {
+    fn new(conditionals: bool) -> Machine {
+        Machine {
+            conditionals,
+            disabled_at: None,
+            sum: 0,
+            trace: None,
+        }
+    }
+
+    /// Records every instruction with its outcome while executing.
+    fn traced(self) -> Machine {
+        Machine {
+            trace: Some(vec![]),
+            ..self
         }
-        None
-    })
+    }
+
+    fn execute(&mut self, token: Token) -> Result<(), ProgramError> {
+        let overflow = || ProgramError::Overflow {
+            position: token.position,
+        };
+        let outcome = match token.instruction {
+            Instruction::Mul(_, _) if self.disabled_at.is_some() => Outcome::Skipped {
+                disabled_at: self.disabled_at,
+            },
+            Instruction::Mul(left, right) => {
+                let result = left.checked_mul(right).ok_or_else(overflow)?;
+                self.sum = self.sum.checked_add(result).ok_or_else(overflow)?;
+                Outcome::Executed { result }
+            }
+            Instruction::Do | Instruction::Dont if !self.conditionals => Outcome::Ignored,
+            Instruction::Do => {
+                self.disabled_at = None;
+                Outcome::Enabled
+            }
+            Instruction::Dont => {
+                self.disabled_at = Some(token.position);
+                Outcome::Disabled
+            }
+        };
+
+        if let Some(trace) = &mut self.trace {
+            trace.push(TraceEntry { token, outcome });
+        }
+
+        Ok(())
+    }
+
+    fn run(mut self, tokens: &[Token]) -> Result<Machine, ProgramError> {
+        for token in tokens {
+            self.execute(*token)?;
+        }
+
+        Ok(self)
+    }
 }
 
 impl DaySolver for Day3Solver {
     fn solve_part1(&self, input: &str) -> Result<String, DayError> {
-        let result: i32 = parse_line_to_multiplication_instructions(input)
-            .map(|instruction| instruction.execute())
-            .sum();
-        Ok(result.to_string())
+        let machine = Machine::new(false).run(&tokenize(input)?)?;
+
+        Ok(machine.sum.to_string())
     }
 
     fn solve_part2(&self, input: &str) -> Result<String, DayError> {
-        let result: i32 = part2_parse_line_to_multiplication_instructions(input)
-            .map(|instruction| instruction.execute())
-            .sum();
-        Ok(result.to_string())
+        let machine = Machine::new(true).run(&tokenize(input)?)?;
+
+        Ok(machine.sum.to_string())
+    }
+
+    fn visualize(&self, input: &str, visualizer: &mut dyn Visualize) -> Result<(), DayError> {
+        let machine = Machine::new(true).traced().run(&tokenize(input)?)?;
+        let trace = machine
+            .trace
+            .unwrap_or_default()
+            .iter()
+            .map(TraceEntry::to_string)
+            .collect::<Vec<_>>();
+
+        visualizer.frame(Frame::from_text(
+            format!("Execution trace, sum {}", machine.sum),
+            &trace.join("\n"),
+        ))?;
+
+        Ok(())
     }
 }
 
@@ -109,4 +260,49 @@ mod tests {
         let solution = Day3Solver {}.solve_part2(get_example_input2()).unwrap();
         assert_eq!(solution, "48")
     }
+
+    #[test]
+    fn test_trace() {
+        let machine = Machine::new(true)
+            .traced()
+            .run(&tokenize(get_example_input2()).unwrap())
+            .unwrap();
+        let trace = machine
+            .trace
+            .unwrap()
+            .iter()
+            .map(TraceEntry::to_string)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            trace,
+            vec![
+                "@1 mul(2,4): executed, result 8",
+                "@20 don't(): disables mul",
+                "@28 mul(5,5): skipped, disabled by don't() @20",
+                "@48 mul(11,8): skipped, disabled by don't() @20",
+                "@59 do(): enables mul",
+                "@64 mul(8,5): executed, result 40",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_huge_operands() {
+        assert_eq!(
+            Day3Solver {}
+                .solve_part1("mul(99999999999,99999999999)")
+                .unwrap_err()
+                .to_string(),
+            "Received invalid input for day: Result of the instruction at position 0 overflows"
+        );
+        assert_eq!(
+            tokenize("mul(1,99999999999999999999)"),
+            Err(ProgramError::OperandOutOfRange {
+                position: 0,
+                operand: "99999999999999999999".to_string()
+            })
+        );
+        assert_eq!(tokenize("mul(,5)mul(4,)"), Ok(vec![]));
+    }
 }
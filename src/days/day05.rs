@@ -1,141 +1,160 @@
 use crate::days::{DayError, DaySolver};
-use std::cmp::Ordering;
-use std::collections::HashMap;
-use std::num::ParseIntError;
+use crate::utils::dependency_graph::{DependencyCycle, DependencyGraph};
+use crate::utils::parse::{lines, pair, separated, ParseError, Span};
+use crate::utils::visualize::{Frame, Visualize};
+use itertools::Itertools;
+use std::fmt::{Display, Formatter};
 
 pub struct Day5Solver;
 
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Debug, Clone, Copy)]
 pub struct Rule {
     pub number_before: u32,
     pub number_after: u32,
 }
 
-fn rules_to_sort_lookup_map(rules: &Vec<Rule>) -> HashMap<u32, Vec<u32>> {
-    let mut lookup: HashMap<u32, Vec<u32>> = HashMap::new();
-
-    for rule in rules {
-        let numbers_after = lookup.entry(rule.number_before).or_insert(vec![]);
-        numbers_after.push(rule.number_after);
+impl Display for Rule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}|{}", self.number_before, self.number_after)
     }
+}
 
-    lookup
+/// Why an update is printed in the wrong order.
+#[derive(PartialEq, Debug)]
+struct Violation {
+    rule: Rule,
+    position_before: usize,
+    position_after: usize,
 }
 
-impl std::str::FromStr for Rule {
-    type Err = DayError;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if let Some((number_before_str, number_after_str)) = s.split_once("|") {
-            return Ok(Rule {
-                number_before: number_before_str
-                    .parse()
-                    .map_err(|e: ParseIntError| DayError::InvalidInputError(e.to_string()))?,
-                number_after: number_after_str
-                    .parse()
-                    .map_err(|e: ParseIntError| DayError::InvalidInputError(e.to_string()))?,
-            });
-        }
-
-        Err(DayError::InvalidInputError(
-            "Cannot split the rule line with |".to_string(),
-        ))
+impl Display for Violation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "page {} at position {} is printed before page {} at position {}, breaking rule {}",
+            self.rule.number_after,
+            self.position_after,
+            self.rule.number_before,
+            self.position_before,
+            self.rule
+        )
     }
 }
 
-fn parse_input(input: &str) -> Result<(Vec<Rule>, Vec<Vec<u32>>), DayError> {
-    if let Some((rule_str, updates_str)) = input.split_once("\n\n") {
-        let rules: Vec<Rule> = rule_str
-            .lines()
-            .map(|rule_line| rule_line.parse())
-            .collect::<Result<Vec<Rule>, DayError>>()?;
-        let updates: Vec<Vec<u32>> = updates_str
-            .lines()
-            .map(|update_line| {
-                update_line
-                    .split(",")
-                    .filter_map(|c| c.parse().ok())
-                    .collect::<Vec<u32>>()
-            })
-            .collect();
+impl From<DependencyCycle<u32>> for DayError {
+    fn from(value: DependencyCycle<u32>) -> Self {
+        DayError::InvalidInputError(value.to_string())
+    }
+}
 
-        return Ok((rules, updates));
+fn rules_to_graph(rules: &[Rule]) -> DependencyGraph<u32> {
+    let mut graph = DependencyGraph::new();
+    for rule in rules {
+        graph.add_dependency(rule.number_before, rule.number_after);
     }
 
-    Err(DayError::InvalidInputError(
-        "Invalid input, cannot split into rule and update lists".to_string(),
-    ))
+    graph
 }
 
-fn sort_by_rule_lookup(
-    lookup_map: &HashMap<u32, Vec<u32>>,
-    number_a: &u32,
-    number_b: &u32,
-) -> bool {
-    match lookup_map.get(number_a) {
-        Some(numbers_after) => numbers_after.contains(number_b),
-        None => false
-    }
+/// The first page that comes too late, paired with the earliest page that should
+/// have come after it.
+fn first_violation(graph: &DependencyGraph<u32>, update: &[u32]) -> Option<Violation> {
+    update
+        .iter()
+        .enumerate()
+        .find_map(|(position_before, page)| {
+            update[..position_before]
+                .iter()
+                .position(|earlier_page| graph.must_precede(page, earlier_page))
+                .map(|position_after| Violation {
+                    rule: Rule {
+                        number_before: *page,
+                        number_after: update[position_after],
+                    },
+                    position_before,
+                    position_after,
+                })
+        })
+}
+
+fn parse_rule(span: Span) -> Result<Rule, ParseError> {
+    let (number_before, number_after) = pair(span, "|", Span::parse, Span::parse)?;
+
+    Ok(Rule {
+        number_before,
+        number_after,
+    })
+}
+
+fn parse_input(input: &str) -> Result<(Vec<Rule>, Vec<Vec<u32>>), DayError> {
+    let (rule_section, update_section) = Span::new(input).split_once("\n\n")?;
+    let rules = lines(rule_section, parse_rule)?;
+    let updates = lines(update_section, |line| separated(line, ",", Span::parse))?;
+
+    Ok((rules, updates))
+}
+
+fn middle_page(update: &[u32]) -> u32 {
+    update[update.len() / 2]
 }
 
 impl DaySolver for Day5Solver {
     fn solve_part1(&self, input: &str) -> Result<String, DayError> {
         let (rules, updates) = parse_input(input)?;
-        let lookup_map = rules_to_sort_lookup_map(&rules);
+        let graph = rules_to_graph(&rules);
 
-        let valid_updates = updates
+        let sum_of_middle_numbers: u32 = updates
             .iter()
-            .filter(|update| {
-                update.is_sorted_by(|number_a, number_b| {
-                    sort_by_rule_lookup(&lookup_map, number_a, number_b)
-                })
-            })
-            .collect::<Vec<_>>();
-
-        let sum_of_middle_numbers: u32 = valid_updates
-            .iter()
-            .map(|update| update[update.len() / 2])
+            .filter(|update| first_violation(&graph, update).is_none())
+            .map(|update| middle_page(update))
             .sum();
 
         Ok(sum_of_middle_numbers.to_string())
     }
 
     fn solve_part2(&self, input: &str) -> Result<String, DayError> {
-        let (rules, mut updates) = parse_input(input)?;
-        let lookup_map = rules_to_sort_lookup_map(&rules);
+        let (rules, updates) = parse_input(input)?;
+        let graph = rules_to_graph(&rules);
 
         let sorted_invalid_updates = updates
-            .iter_mut()
-            .filter(|update| {
-                !update.is_sorted_by(|number_a, number_b| {
-                    sort_by_rule_lookup(&lookup_map, number_a, number_b)
-                })
-            })
-            .map(|update| {
-                update.sort_by(|number_a, number_b| {
-                    if sort_by_rule_lookup(&lookup_map, number_a, number_b) {
-                        Ordering::Less
-                    } else {
-                        Ordering::Greater
-                    }
-                });
-
-                update
-            })
-            .collect::<Vec<_>>();
+            .iter()
+            .filter(|update| first_violation(&graph, update).is_some())
+            .map(|update| graph.topological_sort(update))
+            .collect::<Result<Vec<_>, _>>()?;
 
         let sum_of_middle_numbers: u32 = sorted_invalid_updates
             .iter()
-            .map(|update| update[update.len() / 2])
+            .map(|update| middle_page(update))
             .sum();
 
         Ok(sum_of_middle_numbers.to_string())
     }
+
+    fn report(&self, input: &str, visualizer: &mut dyn Visualize) -> Result<(), DayError> {
+        let (rules, updates) = parse_input(input)?;
+        let graph = rules_to_graph(&rules);
+
+        let violations = updates
+            .iter()
+            .filter_map(|update| {
+                first_violation(&graph, update)
+                    .map(|violation| format!("{}: {}", update.iter().join(","), violation))
+            })
+            .collect::<Vec<_>>();
+
+        visualizer.frame(Frame::from_text(
+            format!("Invalid updates: {} of {}", violations.len(), updates.len()),
+            &violations.join("\n"),
+        ))?;
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::utils::visualize::FrameRecorder;
     fn get_example_input() -> &'static str {
         "\
 47|53
@@ -196,6 +215,47 @@ mod tests {
         assert_eq!(vec![vec![75, 29, 13], vec![61, 13, 29]], updates);
     }
 
+    #[test]
+    fn test_first_violation() {
+        let (rules, updates) = parse_input(get_example_input()).unwrap();
+        let graph = rules_to_graph(&rules);
+
+        assert_eq!(first_violation(&graph, &updates[0]), None);
+        assert_eq!(
+            first_violation(&graph, &updates[3]).unwrap().to_string(),
+            "page 75 at position 0 is printed before page 97 at position 1, breaking rule 97|75"
+        );
+    }
+
+    #[test]
+    fn test_report() {
+        let mut recorder = FrameRecorder::default();
+        Day5Solver {}
+            .report(get_example_input(), &mut recorder)
+            .unwrap();
+
+        assert_eq!(recorder.frames[0].title, "Invalid updates: 3 of 6");
+        assert_eq!(
+            recorder.frames[0].to_text().lines().next(),
+            Some(
+                "75,97,47,61,53: page 75 at position 0 is printed before page 97 at position 1, \
+                 breaking rule 97|75"
+            )
+        );
+    }
+
+    #[test]
+    fn test_cyclic_rules_are_reported() {
+        let error = Day5Solver {}
+            .solve_part2("1|2\n2|3\n3|1\n\n3,2,1")
+            .unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "Received invalid input for day: Dependencies form a cycle: 3 -> 1 -> 2 -> 3"
+        );
+    }
+
     #[test]
     fn test_part1() {
         let solution = Day5Solver {}.solve_part1(get_example_input()).unwrap();
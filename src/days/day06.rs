@@ -1,157 +1,74 @@
 use crate::days::{DayError, DaySolver};
+use crate::utils::bitset::BitSet;
+use crate::utils::direction::Direction4Way;
+use crate::utils::grid::Grid;
+use crate::utils::parse::{grid, Span};
+use crate::utils::point::Point;
+use crate::utils::render::{CellGlyph, GridRenderer};
+use crate::utils::visualize::{Frame, Visualize};
+use itertools::Itertools;
 use std::collections::HashSet;
-use std::ops::Add;
 
 pub struct Day6Solver;
 
-#[derive(Debug, PartialEq, Clone, Hash, Eq)]
-struct Coordinate(i64, i64);
-
-impl Add for Coordinate {
-    type Output = Coordinate;
-
-    fn add(self, rhs: Self) -> Self::Output {
-        Coordinate(self.0 + rhs.0, self.1 + rhs.1)
-    }
-}
-
-#[derive(Debug, PartialEq, Clone, Hash, Eq)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
-}
-
-impl Direction {
-    pub fn turn_right_90_degrees(&self) -> Direction {
-        match self {
-            Direction::Up => Direction::Right,
-            Direction::Down => Direction::Left,
-            Direction::Left => Direction::Up,
-            Direction::Right => Direction::Down,
-        }
-    }
-}
-
-impl From<Direction> for Coordinate {
-    fn from(value: Direction) -> Self {
-        match value {
-            Direction::Up => Coordinate(0, -1),
-            Direction::Down => Coordinate(0, 1),
-            Direction::Left => Coordinate(-1, 0),
-            Direction::Right => Coordinate(1, 0),
-        }
-    }
-}
-
-fn get_from_grid<'a, 'b>(
-    coordinate: &'a Coordinate,
-    grid: &'b Vec<Vec<MapNode>>,
-) -> Option<&'a MapNode>
-where
-    'b: 'a,
-{
-    let row: &Vec<MapNode> = grid.get::<usize>(coordinate.1.try_into().ok()?)?;
-    row.get::<usize>(coordinate.0.try_into().ok()?)
-}
-
-fn insert_obstacle_into_grid(
-    obstacle_position: &Coordinate,
-    grid: &Vec<Vec<MapNode>>,
-) -> Vec<Vec<MapNode>> {
-    let mut new_grid = grid.clone();
-    let row: &mut Vec<MapNode> = new_grid
-        .get_mut::<usize>(obstacle_position.1.try_into().unwrap())
-        .unwrap();
-    let x: usize = obstacle_position.0.try_into().unwrap();
-    row[x] = MapNode::Obstacle;
-
-    new_grid
-}
+type Map = Grid<MapNode>;
 
 #[derive(Debug, Clone)]
 struct Guard {
-    current_position: Coordinate,
-    traversed_path: HashSet<Coordinate>,
-    facing_direction: Direction,
+    current_position: Point,
+    traversed_path: HashSet<Point>,
+    facing_direction: Direction4Way,
 }
 
 impl Guard {
-    pub fn initialize(position: Coordinate) -> Guard {
+    pub fn initialize(position: Point) -> Guard {
         Guard {
             current_position: position,
             traversed_path: HashSet::new(),
-            facing_direction: Direction::Up,
+            facing_direction: Direction4Way::Up,
         }
     }
 
-    pub fn traverse_grid(&mut self, grid: &Vec<Vec<MapNode>>) -> HashSet<Coordinate> {
-        let mut position: Option<Coordinate> = Some(self.current_position.clone());
-
-        while let Some(guard_position) = &position {
-            let coordinate = guard_position.clone() + self.facing_direction.clone().into();
-            let next_position = get_from_grid(&coordinate, grid);
+    /// Walks the guard off the map, calling the observer after every move or turn that
+    /// keeps the guard on it. Fails once the guard is back in a position and direction
+    /// it has been in, as it is then walking in a loop.
+    pub fn traverse_grid_observed(
+        &mut self,
+        grid: &Map,
+        mut observer: impl FnMut(&Guard) -> Result<(), DayError>,
+    ) -> Result<HashSet<Point>, DayError> {
+        let mut states = HashSet::new();
+
+        loop {
+            if !states.insert((self.current_position, self.facing_direction)) {
+                return Err(never_leaves());
+            }
+            let coordinate = self.current_position + Point::screen_step(&self.facing_direction);
 
-            match next_position {
-                None => {
-                    position = None;
+            match grid.get_point(&coordinate) {
+                None => break,
+                Some(MapNode::Path | MapNode::GuardNode) => {
+                    self.traversed_path.insert(coordinate);
+                    self.current_position = coordinate;
+                }
+                Some(MapNode::Obstacle) => {
+                    self.facing_direction = self.facing_direction.turn_90_degrees_to_right()
                 }
-                Some(node) => match node {
-                    MapNode::Path => {
-                        self.traversed_path.insert(coordinate.clone());
-                        self.current_position = coordinate.clone();
-                        position = Some(coordinate.clone());
-                    }
-                    MapNode::Obstacle => {
-                        self.facing_direction =
-                            self.facing_direction.clone().turn_right_90_degrees()
-                    }
-                    MapNode::GuardNode => {}
-                },
             }
+
+            observer(self)?;
         }
 
-        self.traversed_path.clone()
+        Ok(self.traversed_path.clone())
     }
 
-    pub fn will_be_stuck_in_loop(mut self, grid: &Vec<Vec<MapNode>>) -> bool {
-        let mut positions_visited_with_direction: HashSet<(Coordinate, Direction)> = HashSet::new();
-        let mut position: Option<Coordinate> = Some(self.current_position.clone());
-        let mut is_loop = false;
-        while let Some(guard_position) = &position {
-            let coordinate = guard_position.clone() + self.facing_direction.clone().into();
-            let next_position = get_from_grid(&coordinate, grid);
-
-            match next_position {
-                None => {
-                    position = None;
-                }
-                Some(node) => match node {
-                    MapNode::Path => {
-                        if positions_visited_with_direction
-                            .contains(&(coordinate.clone(), self.facing_direction.clone()))
-                        {
-                            is_loop = true;
-                            break;
-                        }
-                        positions_visited_with_direction
-                            .insert((coordinate.clone(), self.facing_direction.clone()));
-                        self.current_position = coordinate.clone();
-                        position = Some(coordinate.clone());
-                    }
-                    MapNode::Obstacle => {
-                        positions_visited_with_direction
-                            .insert((coordinate.clone(), self.facing_direction.clone()));
-                        self.facing_direction =
-                            self.facing_direction.clone().turn_right_90_degrees()
-                    }
-                    MapNode::GuardNode => {}
-                },
-            }
+    fn glyph(&self) -> char {
+        match self.facing_direction {
+            Direction4Way::Up => '^',
+            Direction4Way::Down => 'v',
+            Direction4Way::Left => '<',
+            Direction4Way::Right => '>',
         }
-
-        is_loop
     }
 }
 
@@ -178,83 +95,458 @@ impl TryFrom<char> for MapNode {
     }
 }
 
-fn parse_input(input: &str) -> Result<(Vec<Vec<MapNode>>, Guard), DayError> {
-    let mut initial_guard_position: Option<Coordinate> = None;
-    let map = input
-        .lines()
-        .enumerate()
-        .map(|(y, line)| {
-            line.chars()
-                .enumerate()
-                .map(|(x, char)| {
-                    let node: MapNode = char.try_into()?;
-                    if node == MapNode::GuardNode {
-                        initial_guard_position = Some(Coordinate(
-                            x.try_into().map_err(|_| {
-                                DayError::InvalidInputError(String::from(
-                                    "Cannot convert usize to i64",
-                                ))
-                            })?,
-                            y.try_into().map_err(|_| {
-                                DayError::InvalidInputError(String::from(
-                                    "Cannot convert usize to i64",
-                                ))
-                            })?,
-                        ));
-                        return Ok(MapNode::Path);
-                    }
-
-                    Ok(node)
+impl CellGlyph for MapNode {
+    fn glyph(&self) -> char {
+        match self {
+            MapNode::Path => '.',
+            MapNode::Obstacle => '#',
+            MapNode::GuardNode => '^',
+        }
+    }
+}
+
+/// Jump target of cells in front of which the guard walks off the map.
+const EXIT: u32 = u32::MAX;
+
+/// Directions in the order the guard turns through them, so turning right is `+ 1`.
+const DIRECTIONS: [Direction4Way; 4] = [
+    Direction4Way::Up,
+    Direction4Way::Right,
+    Direction4Way::Down,
+    Direction4Way::Left,
+];
+
+/// Where the guard first stepped onto a cell, the state an obstacle on that cell
+/// would stop the guard in.
+#[derive(Debug, PartialEq)]
+struct Entry {
+    cell: u32,
+    from: u32,
+    direction: usize,
+}
+
+/// Cell the guard stops at in front of an obstacle, walking in the direction.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Stop {
+    cell: u32,
+    direction: usize,
+}
+
+struct Route {
+    visited: BitSet,
+    /// Cells in the order they are walked, starting from the guard's position.
+    cells: Vec<u32>,
+    entries: Vec<Entry>,
+    turns: Vec<Stop>,
+}
+
+/// Position where the guard turned right, facing the obstacle in front.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Turn {
+    position: Point,
+    facing: Direction4Way,
+}
+
+/// Position of an extra obstacle and the turns of the loop it traps the guard in.
+#[derive(Debug, PartialEq)]
+struct Obstruction {
+    position: Point,
+    loop_turns: Vec<Turn>,
+}
+
+impl Obstruction {
+    /// Cells walked between the turns of the loop, split into vertical and
+    /// horizontal ones.
+    fn loop_segments(&self) -> (Vec<Point>, Vec<Point>) {
+        let (mut vertical, mut horizontal) = (vec![], vec![]);
+
+        for (turn, next) in self.loop_turns.iter().circular_tuple_windows() {
+            let facing = turn.facing.turn_90_degrees_to_right();
+            let segment = match facing {
+                Direction4Way::Up | Direction4Way::Down => &mut vertical,
+                Direction4Way::Left | Direction4Way::Right => &mut horizontal,
+            };
+            let mut position = turn.position;
+            while position != next.position {
+                position += Point::screen_step(&facing);
+                segment.push(position);
+            }
+        }
+
+        (vertical, horizontal)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Patrol {
+    route: Vec<Point>,
+    turns: Vec<Turn>,
+    obstructions: Vec<Obstruction>,
+}
+
+/// Flattened map that simulates the guard by jumping from one obstacle to the next.
+/// Cells are indexed row by row, and `jumps` holds for every cell and direction the
+/// cell the guard stops at in front of the next obstacle.
+struct Lab {
+    width: usize,
+    height: usize,
+    obstacles: BitSet,
+    jumps: Vec<[u32; 4]>,
+}
+
+impl Lab {
+    fn new(map: &Map) -> Lab {
+        let (width, height) = (map.width as usize, map.height as usize);
+        let mut obstacles = BitSet::new(width * height);
+        for (point, node) in map.points() {
+            if *node == MapNode::Obstacle {
+                obstacles.insert(point.y as usize * width + point.x as usize);
+            }
+        }
+
+        let mut lab = Lab {
+            width,
+            height,
+            obstacles,
+            jumps: vec![[EXIT; 4]; width * height],
+        };
+        lab.fill_jumps();
+
+        lab
+    }
+
+    /// Sweeps against every direction, so the cell ahead already knows its jump.
+    fn fill_jumps(&mut self) {
+        for (direction_index, direction) in DIRECTIONS.iter().enumerate() {
+            let ys = (0..self.height).collect::<Vec<_>>();
+            let xs = (0..self.width).collect::<Vec<_>>();
+            let (ys, xs): (Vec<_>, Vec<_>) = match direction {
+                Direction4Way::Down => (ys.into_iter().rev().collect(), xs),
+                Direction4Way::Right => (ys, xs.into_iter().rev().collect()),
+                Direction4Way::Up | Direction4Way::Left => (ys, xs),
+            };
+
+            for &y in &ys {
+                for &x in &xs {
+                    let cell = self.cell(x, y);
+                    self.jumps[cell as usize][direction_index] =
+                        match self.ahead(cell, direction_index) {
+                            None => EXIT,
+                            Some(next) if self.obstacles.contains(next as usize) => cell,
+                            Some(next) => self.jumps[next as usize][direction_index],
+                        };
+                }
+            }
+        }
+    }
+
+    fn cell(&self, x: usize, y: usize) -> u32 {
+        (y * self.width + x) as u32
+    }
+
+    fn coordinates(&self, cell: u32) -> (i64, i64) {
+        (
+            (cell as usize % self.width) as i64,
+            (cell as usize / self.width) as i64,
+        )
+    }
+
+    /// Neighbouring cell in the direction, `None` at the edge of the map.
+    fn ahead(&self, cell: u32, direction: usize) -> Option<u32> {
+        let (x, y) = self.coordinates(cell);
+        let step: Point = Point::screen_step(&DIRECTIONS[direction]);
+        let (next_x, next_y) = (x + step.x, y + step.y);
+
+        ((0..self.width as i64).contains(&next_x) && (0..self.height as i64).contains(&next_y))
+            .then(|| self.cell(next_x as usize, next_y as usize))
+    }
+
+    /// Steps from `cell` to `other` if it lies straight ahead in the direction.
+    fn distance_ahead(&self, cell: u32, direction: usize, other: u32) -> Option<i64> {
+        let ((x, y), (other_x, other_y)) = (self.coordinates(cell), self.coordinates(other));
+
+        let distance = match DIRECTIONS[direction] {
+            Direction4Way::Up if x == other_x => y - other_y,
+            Direction4Way::Down if x == other_x => other_y - y,
+            Direction4Way::Left if y == other_y => x - other_x,
+            Direction4Way::Right if y == other_y => other_x - x,
+            _ => return None,
+        };
+
+        (distance > 0).then_some(distance)
+    }
+
+    fn point(&self, cell: u32) -> Point {
+        let (x, y) = self.coordinates(cell);
+
+        Point { x, y }
+    }
+
+    fn turn(&self, stop: &Stop) -> Turn {
+        Turn {
+            position: self.point(stop.cell),
+            facing: DIRECTIONS[stop.direction],
+        }
+    }
+
+    /// Cell-by-cell walk of the guard from `start`, `None` when the guard never
+    /// leaves the map.
+    fn route(&self, start: u32) -> Option<Route> {
+        let mut route = Route {
+            visited: BitSet::new(self.width * self.height),
+            cells: vec![start],
+            entries: vec![],
+            turns: vec![],
+        };
+        let mut states = BitSet::new(self.width * self.height * DIRECTIONS.len());
+        let (mut cell, mut direction) = (start, 0);
+        route.visited.insert(start as usize);
+
+        while let Some(next) = self.ahead(cell, direction) {
+            if !states.insert(cell as usize * DIRECTIONS.len() + direction) {
+                return None;
+            }
+            if self.obstacles.contains(next as usize) {
+                route.turns.push(Stop { cell, direction });
+                direction = (direction + 1) % DIRECTIONS.len();
+                continue;
+            }
+            if route.visited.insert(next as usize) {
+                route.entries.push(Entry {
+                    cell: next,
+                    from: cell,
+                    direction,
+                });
+            }
+            route.cells.push(next);
+            cell = next;
+        }
+
+        Some(route)
+    }
+
+    /// Stops of the loop an extra obstacle on the entered cell traps the guard in,
+    /// `None` when the guard still walks off the map. The walk only visits the cells
+    /// the guard stops at, and remembers those stops with their direction in `stops`,
+    /// which is left empty again for the next check.
+    fn loop_with_obstacle(
+        &self,
+        entry: &Entry,
+        stops: &mut BitSet,
+        touched: &mut Vec<usize>,
+    ) -> Option<Vec<Stop>> {
+        let (mut cell, mut direction) = (entry.from, entry.direction);
+
+        let repeated = loop {
+            let target = self.jumps[cell as usize][direction];
+            let stop = match self.distance_ahead(cell, direction, entry.cell) {
+                Some(distance)
+                    if target == EXIT
+                        || self.distance_ahead(cell, direction, target).unwrap_or(0)
+                            >= distance =>
+                {
+                    let step: Point = Point::screen_step(&DIRECTIONS[direction]);
+                    let (x, y) = self.coordinates(cell);
+                    self.cell(
+                        (x + step.x * (distance - 1)) as usize,
+                        (y + step.y * (distance - 1)) as usize,
+                    )
+                }
+                _ if target == EXIT => break None,
+                _ => target,
+            };
+
+            let state = stop as usize * DIRECTIONS.len() + direction;
+            if !stops.insert(state) {
+                break Some(state);
+            }
+            touched.push(state);
+            cell = stop;
+            direction = (direction + 1) % DIRECTIONS.len();
+        };
+
+        let loop_stops = repeated.map(|repeated| {
+            let loop_start = touched.iter().position(|state| *state == repeated).unwrap();
+            touched[loop_start..]
+                .iter()
+                .map(|state| Stop {
+                    cell: (state / DIRECTIONS.len()) as u32,
+                    direction: state % DIRECTIONS.len(),
+                })
+                .collect()
+        });
+        touched.drain(..).for_each(|state| stops.remove(state));
+
+        loop_stops
+    }
+
+    /// The route of the guard along with every obstruction that traps the guard in a
+    /// loop, in the order the guard first walks onto them.
+    fn patrol(&self, start: u32) -> Option<Patrol> {
+        let route = self.route(start)?;
+        let mut stops = BitSet::new(self.width * self.height * DIRECTIONS.len());
+        let mut touched = vec![];
+        let obstructions = route
+            .entries
+            .iter()
+            .filter_map(|entry| {
+                let loop_stops = self.loop_with_obstacle(entry, &mut stops, &mut touched)?;
+                Some(Obstruction {
+                    position: self.point(entry.cell),
+                    loop_turns: loop_stops.iter().map(|stop| self.turn(stop)).collect(),
                 })
-                .collect::<Result<Vec<MapNode>, DayError>>()
+            })
+            .collect();
+
+        Some(Patrol {
+            route: route.cells.iter().map(|cell| self.point(*cell)).collect(),
+            turns: route.turns.iter().map(|stop| self.turn(stop)).collect(),
+            obstructions,
         })
-        .collect::<Result<Vec<Vec<_>>, _>>()?;
+    }
+}
+
+fn parse_input(input: &str) -> Result<(Map, Guard), DayError> {
+    let mut map = grid(Span::new(input), |char| MapNode::try_from(char).ok())?;
+    let initial_guard_position = map
+        .points()
+        .find(|(_, node)| **node == MapNode::GuardNode)
+        .map(|(point, _)| point);
 
     match initial_guard_position {
-        Some(position) => Ok((map, Guard::initialize(position))),
+        Some(position) => {
+            *map.get_point_mut(&position).unwrap() = MapNode::Path;
+            Ok((map, Guard::initialize(position)))
+        }
         None => Err(DayError::InvalidInputError(
             "Cannot parse the initial guard position from the map".to_string(),
         )),
     }
 }
 
+fn never_leaves() -> DayError {
+    DayError::InvalidInputError("The guard never leaves the map".to_string())
+}
+
+fn patrol(input: &str) -> Result<(Map, Patrol), DayError> {
+    let (grid, guard) = parse_input(input)?;
+    let lab = Lab::new(&grid);
+    let start = lab.cell(
+        guard.current_position.x as usize,
+        guard.current_position.y as usize,
+    );
+    let patrol = lab.patrol(start).ok_or_else(never_leaves)?;
+
+    Ok((grid, patrol))
+}
+
 impl DaySolver for Day6Solver {
     fn solve_part1(&self, input: &str) -> Result<String, DayError> {
-        let (grid, mut guard) = parse_input(input)?;
-        guard.traverse_grid(&grid);
-
-        Ok(guard.traversed_path.len().to_string())
+        let (grid, guard) = parse_input(input)?;
+        let lab = Lab::new(&grid);
+        let start = lab.cell(
+            guard.current_position.x as usize,
+            guard.current_position.y as usize,
+        );
+        let route = lab.route(start).ok_or_else(never_leaves)?;
+
+        Ok(route.visited.len().to_string())
     }
 
     fn solve_part2(&self, input: &str) -> Result<String, DayError> {
-        let (grid, guard) = parse_input(input)?;
-
-        let mut initial_guard = guard.clone();
-        initial_guard.traverse_grid(&grid);
-        let mut obstacle_coordinates_that_cause_infinite_loop: HashSet<Coordinate> = HashSet::new();
+        let (_, patrol) = patrol(input)?;
 
-        for coordinate in initial_guard
-            .traversed_path
-            .iter()
-            .filter(|coordinate| *coordinate != &guard.clone().current_position)
-        {
-            let grid_with_obstacle = insert_obstacle_into_grid(&coordinate, &grid);
-            let test_guard = guard.clone();
+        Ok(patrol.obstructions.len().to_string())
+    }
 
-            if test_guard.will_be_stuck_in_loop(&grid_with_obstacle) {
-                obstacle_coordinates_that_cause_infinite_loop.insert(coordinate.clone());
-            }
+    fn report(&self, input: &str, visualizer: &mut dyn Visualize) -> Result<(), DayError> {
+        let (grid, patrol) = patrol(input)?;
+        let turns = patrol.turns.iter().map(|turn| turn.position);
+        let route = GridRenderer::new(&grid)
+            .overlay(patrol.route.iter().copied(), 'X')
+            .overlay(turns, '+')
+            .overlay(patrol.route.first().copied(), '^');
+
+        visualizer.frame(Frame::from_renderer(
+            format!(
+                "Route of {} steps with {} turns",
+                patrol.route.len() - 1,
+                patrol.turns.len()
+            ),
+            &route,
+        ))?;
+        visualizer.frame(Frame::from_renderer(
+            format!(
+                "{} obstructions trap the guard in a loop",
+                patrol.obstructions.len()
+            ),
+            &route.overlay(
+                patrol
+                    .obstructions
+                    .iter()
+                    .map(|obstruction| obstruction.position),
+                'O',
+            ),
+        ))?;
+
+        for (index, obstruction) in patrol.obstructions.iter().enumerate() {
+            let (vertical, horizontal) = obstruction.loop_segments();
+            let renderer = GridRenderer::new(&grid)
+                .overlay(vertical, '|')
+                .overlay(horizontal, '-')
+                .overlay(obstruction.loop_turns.iter().map(|turn| turn.position), '+')
+                .overlay([obstruction.position], 'O');
+
+            visualizer.frame(Frame::from_renderer(
+                format!(
+                    "Obstruction {}/{} at {}: loop of {} turns",
+                    index + 1,
+                    patrol.obstructions.len(),
+                    obstruction.position,
+                    obstruction.loop_turns.len()
+                ),
+                &renderer,
+            ))?;
         }
 
-        Ok(obstacle_coordinates_that_cause_infinite_loop
-            .len()
-            .to_string())
+        Ok(())
+    }
+
+    fn visualize(&self, input: &str, visualizer: &mut dyn Visualize) -> Result<(), DayError> {
+        let (grid, mut guard) = parse_input(input)?;
+        let start = guard.current_position;
+        let mut step = 0;
+
+        guard.traverse_grid_observed(&grid, |guard| {
+            step += 1;
+            let renderer = GridRenderer::new(&grid)
+                .overlay(guard.traversed_path.iter().copied().chain([start]), 'X')
+                .overlay([guard.current_position], guard.glyph());
+
+            visualizer.frame(Frame::from_renderer(
+                format!(
+                    "Step {step}: {} positions visited",
+                    guard.traversed_path.len()
+                ),
+                &renderer,
+            ))?;
+
+            Ok(())
+        })?;
+
+        Ok(())
     }
 }
 #[cfg(test)]
 mod tests {
-    use crate::days::day06::Day6Solver;
+    use crate::days::day06::{parse_input, patrol, Day6Solver, Map, MapNode, Turn};
     use crate::days::DaySolver;
+    use crate::utils::cycle;
+    use crate::utils::direction::Direction4Way;
+    use crate::utils::point::Point;
+    use crate::utils::render::GridRenderer;
+    use crate::utils::visualize::FrameRecorder;
+    use itertools::Itertools;
 
     fn get_example_input() -> &'static str {
         "\
@@ -283,4 +575,222 @@ mod tests {
 
         assert_eq!(solution, "6")
     }
+
+    /// Map with obstacles scattered by a linear congruential generator, the guard
+    /// standing in the middle.
+    fn generate_map(size: usize, seed: u64) -> String {
+        let mut state = seed;
+        (0..size)
+            .map(|y| {
+                (0..size)
+                    .map(|x| {
+                        state = state
+                            .wrapping_mul(6364136223846793005)
+                            .wrapping_add(1442695040888963407);
+                        match (x, y) {
+                            _ if x == size / 2 && y == size / 2 => '^',
+                            _ if (state >> 33).is_multiple_of(15) => '#',
+                            _ => '.',
+                        }
+                    })
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Walks the guard cell by cell on a copy of the map with the obstacle added.
+    fn loops_naively(grid: &Map, start: Point, obstacle: Point) -> bool {
+        let mut grid = grid.clone();
+        *grid.get_point_mut(&obstacle).unwrap() = MapNode::Obstacle;
+
+        cycle::brent((start, Direction4Way::Up), |(position, direction)| {
+            let ahead = *position + Point::screen_step(direction);
+            match grid.get_point(&ahead)? {
+                MapNode::Obstacle => Some((*position, direction.turn_90_degrees_to_right())),
+                _ => Some((ahead, *direction)),
+            }
+        })
+        .is_some()
+    }
+
+    #[test]
+    fn test_jump_table_matches_naive_walk() {
+        for seed in 0..5 {
+            let input = generate_map(60, seed);
+            let (grid, mut guard) = parse_input(&input).unwrap();
+            let start = guard.current_position;
+            let mut path = guard.traverse_grid_observed(&grid, |_| Ok(())).unwrap();
+            path.insert(start);
+
+            let naive = path
+                .iter()
+                .filter(|point| **point != start && loops_naively(&grid, start, **point))
+                .count();
+
+            assert_eq!(
+                Day6Solver {}.solve_part1(&input).unwrap(),
+                path.len().to_string()
+            );
+            assert_eq!(
+                Day6Solver {}.solve_part2(&input).unwrap(),
+                naive.to_string()
+            );
+        }
+    }
+
+    #[test]
+    fn test_ragged_map_is_rejected() {
+        let error = Day6Solver {}.solve_part1("..\n^.#").unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "Received invalid input for day: line 2, column 1: expected a row of 2 cells, found 3"
+        );
+    }
+
+    #[test]
+    fn test_guard_stuck_from_the_start() {
+        let error = Day6Solver {}.solve_part1(".#.\n#^#\n.#.").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Received invalid input for day: The guard never leaves the map"
+        );
+
+        let mut recorder = FrameRecorder::default();
+        let error = Day6Solver {}
+            .visualize(".#.\n#^#\n.#.", &mut recorder)
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Received invalid input for day: The guard never leaves the map"
+        );
+        assert_eq!(recorder.frames.len(), 4);
+    }
+
+    #[test]
+    fn test_visualizing_guard_passing_its_start() {
+        let mut recorder = FrameRecorder::default();
+        Day6Solver {}
+            .visualize("..#..\n....#\n..^..\n...#.\n.....", &mut recorder)
+            .unwrap();
+
+        assert_eq!(
+            recorder.frames.last().unwrap().to_text(),
+            "..#..\n..XX#\n<XXX.\n...#.\n....."
+        );
+    }
+
+    #[test]
+    fn test_rendering_traversed_path() {
+        let (grid, mut guard) = parse_input(get_example_input()).unwrap();
+        let start = guard.current_position;
+        let path = guard.traverse_grid_observed(&grid, |_| Ok(())).unwrap();
+
+        let rendered = GridRenderer::new(&grid)
+            .overlay(path.into_iter().chain([start]), 'X')
+            .to_string();
+
+        assert_eq!(
+            rendered,
+            "\
+....#.....
+....XXXXX#
+....X...X.
+..#.X...X.
+..XXXXX#X.
+..X.X.X.X.
+.#XXXXXXX.
+.XXXXXXX#.
+#XXXXXXX..
+......#X.."
+        )
+    }
+
+    #[test]
+    fn test_visualizing_guard_walk() {
+        let mut recorder = FrameRecorder::default();
+        Day6Solver {}
+            .visualize(get_example_input(), &mut recorder)
+            .unwrap();
+
+        let first_frame = recorder.frames.first().unwrap();
+        assert_eq!(first_frame.title, "Step 1: 1 positions visited");
+        assert_eq!(first_frame.to_text().lines().nth(5), Some("....^....."));
+
+        let [.., second_to_last_frame, last_frame] = &recorder.frames[..] else {
+            panic!("Expected at least two frames");
+        };
+        assert_eq!(last_frame.to_text().lines().last(), Some("......#v.."));
+        assert_ne!(second_to_last_frame.to_text(), last_frame.to_text());
+    }
+
+    #[test]
+    fn test_patrol_report() {
+        let (_, patrol) = patrol(get_example_input()).unwrap();
+
+        assert_eq!(patrol.route.len(), 45);
+        assert_eq!(patrol.route[0], Point { x: 4, y: 6 });
+        assert_eq!(
+            patrol.turns[..2],
+            [
+                Turn {
+                    position: Point { x: 4, y: 1 },
+                    facing: Direction4Way::Up
+                },
+                Turn {
+                    position: Point { x: 8, y: 1 },
+                    facing: Direction4Way::Right
+                },
+            ]
+        );
+        assert_eq!(
+            patrol
+                .obstructions
+                .iter()
+                .map(|obstruction| obstruction.position)
+                .sorted()
+                .collect::<Vec<_>>(),
+            vec![
+                Point { x: 1, y: 8 },
+                Point { x: 3, y: 6 },
+                Point { x: 3, y: 8 },
+                Point { x: 6, y: 7 },
+                Point { x: 7, y: 7 },
+                Point { x: 7, y: 9 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rendering_obstruction_loops() {
+        let mut recorder = FrameRecorder::default();
+        Day6Solver {}
+            .report(get_example_input(), &mut recorder)
+            .unwrap();
+
+        assert_eq!(recorder.frames.len(), 8);
+        assert_eq!(
+            recorder.frames[1].title,
+            "6 obstructions trap the guard in a loop"
+        );
+        assert_eq!(
+            recorder.frames[2].title,
+            "Obstruction 1/6 at 3,6: loop of 4 turns"
+        );
+        assert_eq!(
+            recorder.frames[2].to_text(),
+            "\
+....#.....
+....+---+#
+....|...|.
+..#.|...|.
+....|..#|.
+....|...|.
+.#.O+---+.
+........#.
+#.........
+......#..."
+        );
+    }
 }
@@ -1,111 +1,331 @@
 use crate::days::{DayError, DaySolver};
+use crate::utils::math::{concat_digits, num_digits, split_digits_at};
+use crate::utils::parse::{integers, lines, pair, Span};
+use crate::utils::visualize::{Frame, Visualize};
+use itertools::Itertools;
+use rayon::prelude::*;
+use std::cmp::Reverse;
+use std::time::{Duration, Instant};
 
 pub struct Day7Solver;
 
+/// Result of undoing an operator, the left operand it must have been applied to.
 #[derive(Debug, PartialEq)]
-enum Operator {
-    Add,
-    Multiply,
-    Concat,
+enum Inverse {
+    Impossible,
+    Left(i64),
+    /// Any left operand gives the result, as when multiplying by zero.
+    Any,
 }
 
-impl Operator {
-    pub fn execute(&self, a: i64, b: i64) -> i64 {
-        match self {
-            Operator::Add => a + b,
-            Operator::Multiply => a * b,
-            Operator::Concat => format!("{a}{b}").parse().unwrap(),
+/// Binary operator placed between the numbers of an equation, which are evaluated
+/// left to right. New operators only need to know how to apply and undo themselves.
+trait Operator: Sync {
+    fn symbol(&self) -> &'static str;
+
+    /// `None` when the result overflows or is not defined for the operands.
+    fn apply(&self, left: i64, right: i64) -> Option<i64>;
+
+    /// The left operand that gives `result` when the operator is applied with `right`.
+    fn undo(&self, result: i64, right: i64) -> Inverse;
+}
+
+struct Add;
+
+impl Operator for Add {
+    fn symbol(&self) -> &'static str {
+        "+"
+    }
+
+    fn apply(&self, left: i64, right: i64) -> Option<i64> {
+        left.checked_add(right)
+    }
+
+    fn undo(&self, result: i64, right: i64) -> Inverse {
+        result
+            .checked_sub(right)
+            .map_or(Inverse::Impossible, Inverse::Left)
+    }
+}
+
+struct Multiply;
+
+impl Operator for Multiply {
+    fn symbol(&self) -> &'static str {
+        "*"
+    }
+
+    fn apply(&self, left: i64, right: i64) -> Option<i64> {
+        left.checked_mul(right)
+    }
+
+    fn undo(&self, result: i64, right: i64) -> Inverse {
+        match (result, right) {
+            (0, 0) => Inverse::Any,
+            _ if result.checked_rem(right) == Some(0) => result
+                .checked_div(right)
+                .map_or(Inverse::Impossible, Inverse::Left),
+            _ => Inverse::Impossible,
+        }
+    }
+}
+
+/// Joins the digits of two non-negative numbers, `12 || 345` being `12345`.
+struct Concat;
+
+impl Operator for Concat {
+    fn symbol(&self) -> &'static str {
+        "||"
+    }
+
+    fn apply(&self, left: i64, right: i64) -> Option<i64> {
+        let joined = concat_digits(u64::try_from(left).ok()?, u64::try_from(right).ok()?)?;
+
+        i64::try_from(joined).ok()
+    }
+
+    fn undo(&self, result: i64, right: i64) -> Inverse {
+        let (Ok(result), Ok(right)) = (u64::try_from(result), u64::try_from(right)) else {
+            return Inverse::Impossible;
+        };
+
+        match split_digits_at(result, num_digits(right)) {
+            (left, suffix) if suffix == right => Inverse::Left(left as i64),
+            _ => Inverse::Impossible,
         }
     }
 }
 
+const ADD_AND_MULTIPLY: &[&dyn Operator] = &[&Add, &Multiply];
+const ADD_MULTIPLY_AND_CONCAT: &[&dyn Operator] = &[&Add, &Multiply, &Concat];
+
+/// Amount of equations listed in the timing breakdown of the report.
+const SLOWEST_EQUATIONS: usize = 10;
+
 #[derive(Debug, PartialEq)]
 struct Equation {
+    line: usize,
     target: i64,
     numbers: Vec<i64>,
 }
 
 impl Equation {
-    pub fn is_solvable(&self, allowed_operators: &Vec<Operator>) -> bool {
-        Self::has_solution(allowed_operators, &self.numbers, 0, self.target)
+    /// Operators to put between the numbers so that they evaluate to the target.
+    /// Searches backwards from the target, so operators that cannot be undone with
+    /// the last number, like a division with a remainder or a missing suffix, prune
+    /// the whole branch.
+    pub fn solve<'a>(&self, operators: &[&'a dyn Operator]) -> Option<Vec<&'a dyn Operator>> {
+        let (first, rest) = self.numbers.split_first()?;
+        let mut sequence = Vec::with_capacity(rest.len());
+
+        Self::undo_from(operators, *first, rest, self.target, &mut sequence).then_some(sequence)
     }
 
-    fn has_solution(operators: &Vec<Operator>, numbers: &[i64], current: i64, target: i64) -> bool {
-        if current > target {
-            return false;
-        }
+    /// Whether the numbers after `first` can be undone from `result` back to `first`.
+    /// Operators are pushed after the ones before them, so the sequence ends up in order.
+    fn undo_from<'a>(
+        operators: &[&'a dyn Operator],
+        first: i64,
+        numbers: &[i64],
+        result: i64,
+        sequence: &mut Vec<&'a dyn Operator>,
+    ) -> bool {
+        let Some((last, init)) = numbers.split_last() else {
+            return result == first;
+        };
 
-        if numbers.len() == 0 {
-            return current == target;
-        }
+        operators.iter().any(|operator| {
+            let is_solved = match operator.undo(result, *last) {
+                Inverse::Impossible => false,
+                Inverse::Left(left) => Self::undo_from(operators, first, init, left, sequence),
+                Inverse::Any => Self::evaluate_any(operators, first, init, sequence),
+            };
+            if is_solved {
+                sequence.push(*operator);
+            }
+
+            is_solved
+        })
+    }
+
+    /// Whether the numbers evaluate to anything at all without overflowing.
+    fn evaluate_any<'a>(
+        operators: &[&'a dyn Operator],
+        current: i64,
+        numbers: &[i64],
+        sequence: &mut Vec<&'a dyn Operator>,
+    ) -> bool {
+        let Some((next, rest)) = numbers.split_first() else {
+            return true;
+        };
 
-        let next_number = numbers[0];
         operators.iter().any(|operator| {
-            Self::has_solution(
-                operators,
-                &numbers[1..],
-                operator.execute(current, next_number),
-                target,
-            )
+            operator.apply(current, *next).is_some_and(|value| {
+                sequence.push(*operator);
+                let is_solved = Self::evaluate_any(operators, value, rest, sequence);
+                if !is_solved {
+                    sequence.pop();
+                }
+
+                is_solved
+            })
         })
     }
+
+    /// The equation written out with the operators, as in `3267 = 81 + 40 * 27`.
+    fn format_with(&self, sequence: &[&dyn Operator]) -> String {
+        let mut expression = self.numbers.iter().map(i64::to_string);
+        let mut formatted = format!(
+            "{} = {}",
+            self.target,
+            expression.next().unwrap_or_default()
+        );
+        for (operator, number) in sequence.iter().zip(expression) {
+            formatted += &format!(" {} {}", operator.symbol(), number);
+        }
+
+        formatted
+    }
+}
+
+fn parse_input(input: &str) -> Result<Vec<Equation>, DayError> {
+    Ok(lines(Span::new(input), |line| {
+        let (target, numbers) = pair(line, ":", Span::parse, integers)?;
+
+        Ok(Equation {
+            line: line.line(),
+            target,
+            numbers,
+        })
+    })?)
+}
+
+/// Outcome of solving a single equation along with how long the search took.
+struct Checked<'a, 'o> {
+    equation: &'a Equation,
+    sequence: Option<Vec<&'o dyn Operator>>,
+    elapsed: Duration,
 }
 
-fn parse_input(input: &str) -> Vec<Equation> {
-    input
-        .lines()
-        .map(|line| {
-            let (target_str, numbers_str) = line
-                .split_once(":")
-                .ok_or(DayError::InvalidInputError(
-                    "Could not parse Equation input".to_string(),
-                ))
-                .unwrap();
-            Equation {
-                target: target_str.parse().unwrap(),
-                numbers: numbers_str
-                    .trim()
-                    .split(" ")
-                    .map(|number| number.parse().unwrap())
-                    .collect(),
+/// Solves the equations spread across all cores, keeping them in input order.
+fn check_equations<'a, 'o>(
+    equations: &'a [Equation],
+    operators: &[&'o dyn Operator],
+) -> Vec<Checked<'a, 'o>> {
+    equations
+        .par_iter()
+        .map(|equation| {
+            let start = Instant::now();
+            let sequence = equation.solve(operators);
+
+            Checked {
+                equation,
+                sequence,
+                elapsed: start.elapsed(),
             }
         })
         .collect()
 }
 
+/// Sum of the targets of the equations that can be solved with the operators.
+fn total_calibration_result(input: &str, operators: &[&dyn Operator]) -> Result<i64, DayError> {
+    check_equations(&parse_input(input)?, operators)
+        .iter()
+        .filter(|checked| checked.sequence.is_some())
+        .try_fold(0i64, |sum, checked| {
+            sum.checked_add(checked.equation.target)
+        })
+        .ok_or_else(|| {
+            DayError::InvalidInputError("The total calibration result overflows".to_string())
+        })
+}
+
 impl DaySolver for Day7Solver {
     fn solve_part1(&self, input: &str) -> Result<String, DayError> {
-        let valid_equations: Vec<Equation> = parse_input(input)
-            .into_iter()
-            .filter(|equation| equation.is_solvable(&vec![Operator::Add, Operator::Multiply]))
-            .collect();
-
-        let sum_of_equations: i64 = valid_equations.iter().map(|equation| equation.target).sum();
-
-        Ok(sum_of_equations.to_string())
+        Ok(total_calibration_result(input, ADD_AND_MULTIPLY)?.to_string())
     }
 
     fn solve_part2(&self, input: &str) -> Result<String, DayError> {
-        let valid_equations: Vec<Equation> = parse_input(input)
-            .into_iter()
-            .filter(|equation| {
-                equation.is_solvable(&vec![Operator::Add, Operator::Multiply, Operator::Concat])
+        Ok(total_calibration_result(input, ADD_MULTIPLY_AND_CONCAT)?.to_string())
+    }
+
+    fn report(&self, input: &str, visualizer: &mut dyn Visualize) -> Result<(), DayError> {
+        let equations = parse_input(input)?;
+        let checked = check_equations(&equations, ADD_MULTIPLY_AND_CONCAT);
+        let solutions = checked
+            .iter()
+            .map(|checked| match &checked.sequence {
+                Some(sequence) => checked.equation.format_with(sequence),
+                None => format!(
+                    "{}: {} has no solution",
+                    checked.equation.target,
+                    checked.equation.numbers.iter().join(" ")
+                ),
+            })
+            .collect::<Vec<_>>();
+        let slowest = checked
+            .iter()
+            .sorted_by_key(|checked| (Reverse(checked.elapsed), checked.equation.line))
+            .take(SLOWEST_EQUATIONS)
+            .map(|checked| {
+                format!(
+                    "line {}: {:?} for {} numbers",
+                    checked.equation.line,
+                    checked.elapsed,
+                    checked.equation.numbers.len()
+                )
             })
-            .collect();
+            .collect::<Vec<_>>();
 
-        let sum_of_equations: i64 = valid_equations.iter().map(|equation| equation.target).sum();
+        visualizer.frame(Frame::from_text(
+            "Operators solving the equations",
+            &solutions.join("\n"),
+        ))?;
+        visualizer.frame(Frame::from_text(
+            format!(
+                "Slowest equations, {:?} in total",
+                checked
+                    .iter()
+                    .map(|checked| checked.elapsed)
+                    .sum::<Duration>()
+            ),
+            &slowest.join("\n"),
+        ))?;
 
-        Ok(sum_of_equations.to_string())
+        Ok(())
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::days::day07::{parse_input, Day7Solver, Equation};
+    use crate::days::day07::{
+        check_equations, parse_input, Add, Concat, Day7Solver, Equation, Inverse, Multiply,
+        Operator, ADD_MULTIPLY_AND_CONCAT,
+    };
     use crate::days::DaySolver;
+    use crate::utils::visualize::FrameRecorder;
+    use itertools::Itertools;
+
+    struct Subtract;
+
+    impl Operator for Subtract {
+        fn symbol(&self) -> &'static str {
+            "-"
+        }
+
+        fn apply(&self, left: i64, right: i64) -> Option<i64> {
+            left.checked_sub(right)
+        }
+
+        fn undo(&self, result: i64, right: i64) -> Inverse {
+            result
+                .checked_add(right)
+                .map_or(Inverse::Impossible, Inverse::Left)
+        }
+    }
 
     fn get_example_input() -> &'static str {
-        return "\
+        "\
 190: 10 19
 3267: 81 40 27
 83: 17 5
@@ -114,7 +334,7 @@ mod tests {
 161011: 16 10 13
 192: 17 8 14
 21037: 9 7 18 13
-292: 11 6 16 20";
+292: 11 6 16 20"
     }
 
     #[test]
@@ -122,16 +342,20 @@ mod tests {
         let parsed = parse_input(
             "\
 12: 10 19
+
 13: 1 2",
-        );
+        )
+        .unwrap();
         assert_eq!(
             parsed,
             vec![
                 Equation {
+                    line: 1,
                     target: 12,
                     numbers: vec![10, 19]
                 },
                 Equation {
+                    line: 3,
                     target: 13,
                     numbers: vec![1, 2]
                 }
@@ -151,4 +375,111 @@ mod tests {
 
         assert_eq!(solution, "11387")
     }
+
+    fn solve_with(equation: &str, operators: &[&dyn Operator]) -> Option<String> {
+        let equation = parse_input(equation).unwrap().remove(0);
+
+        equation
+            .solve(operators)
+            .map(|sequence| equation.format_with(&sequence))
+    }
+
+    #[test]
+    fn test_solving_returns_the_operators() {
+        assert_eq!(
+            solve_with("3267: 81 40 27", ADD_MULTIPLY_AND_CONCAT),
+            Some("3267 = 81 * 40 + 27".to_string())
+        );
+        assert_eq!(
+            solve_with("7290: 6 8 6 15", ADD_MULTIPLY_AND_CONCAT),
+            Some("7290 = 6 * 8 || 6 * 15".to_string())
+        );
+        assert_eq!(solve_with("83: 17 5", ADD_MULTIPLY_AND_CONCAT), None);
+        assert_eq!(
+            solve_with("0: 3 4 0", &[&Add, &Multiply]),
+            Some("0 = 3 + 4 * 0".to_string())
+        );
+        assert_eq!(solve_with("5: 5", &[&Add]), Some("5 = 5".to_string()));
+    }
+
+    #[test]
+    fn test_registering_new_operators() {
+        assert_eq!(solve_with("5: 10 2 3", &[&Add, &Multiply]), None);
+        assert_eq!(
+            solve_with("5: 10 2 3", &[&Add, &Multiply, &Subtract]),
+            Some("5 = 10 - 2 - 3".to_string())
+        );
+    }
+
+    #[test]
+    fn test_overflow_is_not_a_solution() {
+        assert_eq!(Multiply.apply(i64::MAX, 2), None);
+        assert_eq!(Concat.apply(i64::MAX, 1), None);
+        assert_eq!(Concat.apply(-1, 1), None);
+        assert_eq!(Concat.undo(12345, 345), Inverse::Left(12));
+        assert_eq!(Concat.undo(12345, 45), Inverse::Left(123));
+        assert_eq!(Concat.undo(12345, 5432), Inverse::Impossible);
+        assert_eq!(Multiply.undo(i64::MIN, -1), Inverse::Impossible);
+        assert_eq!(
+            solve_with(
+                "9223372036854775807: 9223372036854775807 1 1",
+                ADD_MULTIPLY_AND_CONCAT
+            ),
+            Some("9223372036854775807 = 9223372036854775807 * 1 * 1".to_string())
+        );
+        assert_eq!(
+            Day7Solver {}
+                .solve_part1("9223372036854775807: 9223372036854775807\n1: 1")
+                .unwrap_err()
+                .to_string(),
+            "Received invalid input for day: The total calibration result overflows"
+        );
+    }
+
+    #[test]
+    fn test_report() {
+        let mut recorder = FrameRecorder::default();
+        Day7Solver {}
+            .report("190: 10 19\n83: 17 5", &mut recorder)
+            .unwrap();
+
+        assert_eq!(
+            recorder.frames[0].to_text(),
+            "190 = 10 * 19\n83: 17 5 has no solution"
+        );
+        assert!(recorder.frames[1].title.starts_with("Slowest equations"));
+        assert_eq!(
+            recorder.frames[1]
+                .to_text()
+                .lines()
+                .map(|line| line.split(':').next().unwrap())
+                .sorted()
+                .collect::<Vec<_>>(),
+            vec!["line 1", "line 2"]
+        );
+    }
+
+    #[test]
+    fn test_parallel_checks_keep_input_order() {
+        let equations = parse_input(get_example_input()).unwrap();
+        let checked = check_equations(&equations, ADD_MULTIPLY_AND_CONCAT);
+
+        assert_eq!(
+            checked
+                .iter()
+                .map(|checked| (checked.equation.line, checked.sequence.is_some()))
+                .collect::<Vec<_>>(),
+            vec![
+                (1, true),
+                (2, true),
+                (3, false),
+                (4, true),
+                (5, true),
+                (6, false),
+                (7, true),
+                (8, false),
+                (9, true),
+            ]
+        );
+    }
 }
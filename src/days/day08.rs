@@ -1,129 +1,108 @@
 use crate::days::{DayError, DaySolver};
+use crate::utils::grid::Grid;
+use crate::utils::point::Point;
+use crate::utils::render::GridRenderer;
+use crate::utils::visualize::{Frame, Visualize};
 use itertools::Itertools;
 use std::collections::{HashMap, HashSet};
 use std::iter::successors;
-use std::ops::{Add, Neg, Sub};
 
 pub struct Day8Solver;
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 struct Frequency(char);
 
-#[derive(Debug, Eq, PartialEq, Hash, Clone)]
-struct Point {
-    x: isize,
-    y: isize,
-}
-
-impl Sub for &Point {
-    type Output = Point;
-
-    fn sub(self, rhs: Self) -> Self::Output {
-        Point {
-            x: self.x - rhs.x,
-            y: self.y - rhs.y,
-        }
-    }
-}
-
-impl Add for &Point {
-    type Output = Point;
-
-    fn add(self, rhs: Self) -> Self::Output {
-        Point {
-            x: self.x + rhs.x,
-            y: self.y + rhs.y,
-        }
-    }
-}
-
-impl Point {
-    pub fn from_index(x: usize, y: usize) -> Point {
-        Point {
-            x: x.try_into().unwrap(),
-            y: y.try_into().unwrap(),
-        }
-    }
-}
-
-impl Neg for &Point {
-    type Output = Point;
-
-    fn neg(self) -> Self::Output {
-        Point {
-            x: -self.x,
-            y: -self.y
-        }
-    }
-}
-
 struct Antenna {
     point: Point,
 }
 
 type AntennaMap = HashMap<Frequency, Vec<Antenna>>;
 
+/// Where antinodes appear on the line through a pair of antennas of the same frequency.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum AntinodeRule {
+    /// Beyond each antenna at the given multiples of the distance between the two.
+    FixedMultiples(&'static [i64]),
+    /// Every multiple of the distance between the antennas, the antennas included.
+    Harmonics,
+    /// Every lattice point on the line, stepping by the distance divided by the gcd
+    /// of its components so that points between the multiples are not skipped.
+    Reduced,
+}
+
 struct Map {
-    height: isize,
-    width: isize,
+    height: i64,
+    width: i64,
     antennas: AntennaMap,
 }
 
 impl Map {
     fn is_within(&self, point: &Point) -> bool {
-        (point.x >= 0 && point.x <= self.width - 1) && (point.y >= 0 && point.y <= self.height - 1)
+        (point.x >= 0 && point.x < self.width) && (point.y >= 0 && point.y < self.height)
     }
 
-    fn cast_ray<'a,'b>(&self, start_point: &'a Point, difference: &'b Point) -> impl Iterator<Item = Point>  + use<'a, 'b, '_> {
-        successors(Some(start_point.clone()), |point| {
-            let next = point + difference;
+    /// Points from `start_point` onwards in steps of `step` until leaving the map.
+    fn cast_ray(&self, start_point: Point, step: Point) -> impl Iterator<Item = Point> + '_ {
+        successors(Some(start_point), move |point| {
+            let next = point + &step;
             if self.is_within(&next) {
-                return Some(next.clone());
+                return Some(next);
             }
             None
         })
     }
 
-    pub fn get_antinodes_part1(&self) -> HashSet<Point> {
-        self.antennas
-            .values()
-            .flat_map(|antenna_points| {
-                antenna_points
-                    .iter()
-                    .tuple_combinations()
-                    .flat_map(|(antenna_a, antenna_b)| {
-                        let difference = &antenna_a.point - &antenna_b.point;
-                        [
-                            &antenna_a.point + &difference,
-                            &antenna_b.point - &difference,
-                        ]
-                        .into_iter()
-                        .filter(|point| self.is_within(point))
-                    })
-                    .collect::<Vec<Point>>()
-            })
-            .collect::<HashSet<_>>()
+    fn pair_antinodes(&self, a: Point, b: Point, rule: AntinodeRule) -> Vec<Point> {
+        match rule {
+            AntinodeRule::FixedMultiples(multiples) => self.multiples(a, b, multiples),
+            AntinodeRule::Harmonics => self.line(a, a - b),
+            AntinodeRule::Reduced => self.line(a, (a - b).reduced()),
+        }
     }
 
-    pub fn get_antinodes_part2(&self) -> HashSet<Point> {
+    /// Points beyond `a` and `b` at the multiples of the distance between them.
+    fn multiples(&self, a: Point, b: Point, multiples: &[i64]) -> Vec<Point> {
+        let difference = a - b;
+
+        multiples
+            .iter()
+            .flat_map(|multiple| [a + difference * *multiple, b - difference * *multiple])
+            .filter(|point| self.is_within(point))
+            .collect()
+    }
+
+    /// Points on the map stepping both ways from `start`.
+    fn line(&self, start: Point, step: Point) -> Vec<Point> {
+        self.cast_ray(start, step)
+            .chain(self.cast_ray(start, -step))
+            .collect()
+    }
+
+    /// Antinodes of every frequency following the rule, each frequency on its own.
+    pub fn antinodes(&self, rule: AntinodeRule) -> HashMap<Frequency, HashSet<Point>> {
         self.antennas
-            .values()
-            .flat_map(|antenna_points| {
-                antenna_points
+            .iter()
+            .map(|(frequency, antennas)| {
+                let antinodes = antennas
                     .iter()
                     .tuple_combinations()
                     .flat_map(|(antenna_a, antenna_b)| {
-                        let difference = &antenna_a.point - &antenna_b.point;
-                        let mut antenna_a_ray = self.cast_ray(&antenna_a.point, &difference).collect::<Vec<_>>();
-                        let negative_difference = -&difference;
-                        let antenna_b_ray = self.cast_ray(&antenna_b.point, &negative_difference);
-                        antenna_a_ray.extend(antenna_b_ray);
-
-                        antenna_a_ray
+                        self.pair_antinodes(antenna_a.point, antenna_b.point, rule)
                     })
-                    .collect::<Vec<Point>>()
+                    .collect();
+
+                (frequency.clone(), antinodes)
             })
+            .collect()
+    }
+
+    /// Positions with an antinode of any frequency.
+    pub fn count_antinodes(&self, rule: AntinodeRule) -> usize {
+        self.antinodes(rule)
+            .into_values()
+            .flatten()
             .collect::<HashSet<_>>()
+            .len()
     }
 }
 
@@ -156,23 +135,61 @@ fn parse_input(input: &str) -> Map {
 impl DaySolver for Day8Solver {
     fn solve_part1(&self, input: &str) -> Result<String, DayError> {
         let map = parse_input(input);
-        let antinodes = map.get_antinodes_part1();
 
-        Ok(antinodes.len().to_string())
+        Ok(map
+            .count_antinodes(AntinodeRule::FixedMultiples(&[1]))
+            .to_string())
     }
 
     fn solve_part2(&self, input: &str) -> Result<String, DayError> {
         let map = parse_input(input);
-        let antinodes = map.get_antinodes_part2();
 
-        Ok(antinodes.len().to_string())
+        Ok(map.count_antinodes(AntinodeRule::Reduced).to_string())
+    }
+
+    fn report(&self, input: &str, visualizer: &mut dyn Visualize) -> Result<(), DayError> {
+        let map = parse_input(input);
+        let counts = [
+            ("Fixed multiples", AntinodeRule::FixedMultiples(&[1])),
+            ("Harmonics", AntinodeRule::Harmonics),
+            ("Reduced steps", AntinodeRule::Reduced),
+        ]
+        .map(|(name, rule)| format!("{name}: {}", map.count_antinodes(rule)));
+        visualizer.frame(Frame::from_text("Antinodes per rule", &counts.join("\n")))?;
+
+        let empty = Grid::make(vec![vec!['.'; map.width as usize]; map.height as usize]);
+        let antinodes = map.antinodes(AntinodeRule::Reduced);
+        for (frequency, antennas) in map
+            .antennas
+            .iter()
+            .sorted_by_key(|(frequency, _)| frequency.0)
+        {
+            let renderer = GridRenderer::new(&empty)
+                .overlay(antinodes[frequency].iter().copied(), '#')
+                .overlay(antennas.iter().map(|antenna| antenna.point), frequency.0);
+
+            visualizer.frame(Frame::from_renderer(
+                format!(
+                    "Frequency '{}': {} antennas, {} antinodes",
+                    frequency.0,
+                    antennas.len(),
+                    antinodes[frequency].len()
+                ),
+                &renderer,
+            ))?;
+        }
+
+        Ok(())
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::days::day08::Day8Solver;
+    use crate::days::day08::{parse_input, AntinodeRule, Day8Solver, Frequency};
     use crate::days::DaySolver;
+    use crate::utils::point::Point;
+    use crate::utils::visualize::FrameRecorder;
+    use std::collections::HashSet;
 
     pub fn get_test_input() -> &'static str {
         "\
@@ -200,4 +217,75 @@ mod tests {
         let solution = Day8Solver {}.solve_part2(get_test_input()).unwrap();
         assert_eq!(solution, "34")
     }
+
+    fn antinode_count(input: &str, rule: AntinodeRule) -> usize {
+        parse_input(input).count_antinodes(rule)
+    }
+
+    #[test]
+    fn test_antinode_rules() {
+        assert_eq!(
+            antinode_count(get_test_input(), AntinodeRule::Harmonics),
+            34
+        );
+        assert_eq!(antinode_count(get_test_input(), AntinodeRule::Reduced), 34);
+        assert_eq!(
+            antinode_count(get_test_input(), AntinodeRule::FixedMultiples(&[1, 2])),
+            20
+        );
+
+        let spread_out = "a....\n.....\n..a..\n.....\n.....";
+        assert_eq!(antinode_count(spread_out, AntinodeRule::Harmonics), 3);
+        assert_eq!(antinode_count(spread_out, AntinodeRule::Reduced), 5);
+        assert_eq!(
+            antinode_count(spread_out, AntinodeRule::FixedMultiples(&[1])),
+            1
+        );
+    }
+
+    #[test]
+    fn test_antinodes_are_grouped_by_frequency() {
+        let antinodes =
+            parse_input("a.a..\n.b...\n..b..").antinodes(AntinodeRule::FixedMultiples(&[1]));
+
+        assert_eq!(antinodes.len(), 2);
+        assert_eq!(
+            antinodes[&Frequency('a')],
+            HashSet::from([Point { x: 4, y: 0 }])
+        );
+        assert_eq!(
+            antinodes[&Frequency('b')],
+            HashSet::from([Point { x: 0, y: 0 }])
+        );
+    }
+
+    #[test]
+    fn test_report() {
+        let mut recorder = FrameRecorder::default();
+        Day8Solver {}
+            .report("a....\n.....\n..a..\n.....\n.....", &mut recorder)
+            .unwrap();
+
+        assert_eq!(
+            recorder.frames[0].to_text(),
+            "Fixed multiples: 1\nHarmonics: 3\nReduced steps: 5"
+        );
+        assert_eq!(
+            recorder.frames[1].title,
+            "Frequency 'a': 2 antennas, 5 antinodes"
+        );
+        assert_eq!(
+            recorder.frames[1].to_text(),
+            "a....\n.#...\n..a..\n...#.\n....#"
+        );
+    }
+
+    #[test]
+    fn test_empty_input() {
+        let mut recorder = FrameRecorder::default();
+        Day8Solver {}.report("", &mut recorder).unwrap();
+
+        assert_eq!(recorder.frames.len(), 1);
+        assert_eq!(Day8Solver {}.solve_part2("").unwrap(), "0");
+    }
 }
@@ -1,5 +1,10 @@
 use crate::days::{DayError, DaySolver};
+use crate::utils::visualize::{Frame, Visualize};
 use itertools::Itertools;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fmt::{Display, Formatter};
+
 pub struct Day9Solver;
 
 #[derive(Debug, Clone, PartialEq)]
@@ -14,18 +19,263 @@ struct FileBlock(u64);
 
 type FileBlocks = Vec<Option<FileBlock>>;
 
-impl FileMapEntry {
-    fn to_blocks(&self) -> FileBlocks {
-        let mut block: FileBlocks = (0..self.file_size)
-            .map(|_| Some(FileBlock(self.file_id)))
-            .collect();
-        let free_space: FileBlocks = (0..self.free_space).map(|_| None).collect();
-        block.extend(free_space);
+/// Blocks taken up by a file, or by a part of it once files get split up.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct FileSpan {
+    file_id: u64,
+    start: usize,
+    length: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct FreeSpan {
+    start: usize,
+    length: usize,
+}
+
+/// Blocks of a file moved to the left. The blocks of a file are interchangeable, so a
+/// move only tells where they went; `single_blocks` replays it the way the puzzle does.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Move {
+    file_id: u64,
+    from: usize,
+    to: usize,
+    length: usize,
+}
+
+impl Move {
+    fn apply(&self, blocks: &mut FileBlocks) {
+        (0..self.length).for_each(|offset| blocks.swap(self.from + offset, self.to + offset));
+    }
 
-        block
+    /// The same move made one block at a time, starting from the last block as the
+    /// puzzle does when it fragments files.
+    fn single_blocks(&self) -> impl Iterator<Item = Move> + '_ {
+        (0..self.length).map(|offset| Move {
+            file_id: self.file_id,
+            from: self.from + self.length - 1 - offset,
+            to: self.to + offset,
+            length: 1,
+        })
     }
 }
 
+impl Display for Move {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "file {}: blocks {}..{} moved to {}..{}",
+            self.file_id,
+            self.from,
+            self.from + self.length,
+            self.to,
+            self.to + self.length
+        )
+    }
+}
+
+/// Files of a compacted disk ordered by position, along with the moves that got them there.
+#[derive(Debug, PartialEq)]
+struct Compaction {
+    files: Vec<FileSpan>,
+    moves: Vec<Move>,
+}
+
+/// Files and free spaces of the disk map laid out in block positions.
+#[derive(Debug, PartialEq)]
+struct Disk {
+    files: Vec<FileSpan>,
+    free: Vec<FreeSpan>,
+}
+
+impl Disk {
+    fn new(entries: &[FileMapEntry]) -> Disk {
+        let mut disk = Disk {
+            files: Vec::with_capacity(entries.len()),
+            free: Vec::with_capacity(entries.len()),
+        };
+        let mut position = 0;
+        for entry in entries {
+            let (file_size, free_space) = (entry.file_size as usize, entry.free_space as usize);
+            disk.files.push(FileSpan {
+                file_id: entry.file_id,
+                start: position,
+                length: file_size,
+            });
+            disk.free.push(FreeSpan {
+                start: position + file_size,
+                length: free_space,
+            });
+            position += file_size + free_space;
+        }
+
+        disk
+    }
+
+    /// Moves blocks one at a time from the end of the disk into the leftmost free
+    /// block, splitting files up. One pointer walks the free spans from the left and
+    /// another the files from the right, until they meet.
+    fn compact_blocks(&self) -> Compaction {
+        let mut files = self.files.clone();
+        let mut moved = vec![];
+        let mut moves = vec![];
+        let mut free_spans = self.free.iter().copied();
+        let mut free = free_spans.next();
+
+        while let Some(file) = files.last_mut() {
+            if file.length == 0 {
+                files.pop();
+                continue;
+            }
+            let Some(span) = free.as_mut().filter(|span| span.start < file.start) else {
+                break;
+            };
+
+            let length = span.length.min(file.length);
+            if length > 0 {
+                moved.push(FileSpan {
+                    file_id: file.file_id,
+                    start: span.start,
+                    length,
+                });
+                moves.push(Move {
+                    file_id: file.file_id,
+                    from: file.start + file.length - length,
+                    to: span.start,
+                    length,
+                });
+            }
+            file.length -= length;
+            span.start += length;
+            span.length -= length;
+
+            if file.length == 0 {
+                files.pop();
+            }
+            if span.length == 0 {
+                free = free_spans.next();
+            }
+        }
+
+        files.extend(moved);
+        files.sort_by_key(|file| file.start);
+
+        Compaction { files, moves }
+    }
+
+    /// Free spans with the ones only separated by empty files joined together.
+    fn free_runs(&self) -> Vec<FreeSpan> {
+        let mut runs: Vec<FreeSpan> = Vec::with_capacity(self.free.len());
+        for span in &self.free {
+            match runs.last_mut() {
+                Some(run) if run.start + run.length == span.start => run.length += span.length,
+                _ => runs.push(*span),
+            }
+        }
+
+        runs
+    }
+
+    /// Moves whole files, from the highest id down, into the leftmost free span they
+    /// fit in. Free spans are kept in min-heaps by start position, one per length up
+    /// to the longest file, with every longer span in the last heap. The leftmost
+    /// fitting span is then the smallest top among the heaps from the file's length
+    /// up, which is at most nine heaps for a disk map. The space a file leaves behind
+    /// never gets used, as every file still to move lies to the left of it.
+    fn compact_files(&self) -> Compaction {
+        let longest_file = self.files.iter().map(|file| file.length).max().unwrap_or(0);
+        let bucket = |length: usize| length.min(longest_file);
+        let mut free_by_length = vec![BinaryHeap::new(); longest_file + 1];
+        for span in self.free_runs().iter().filter(|span| span.length > 0) {
+            free_by_length[bucket(span.length)].push(Reverse((span.start, span.length)));
+        }
+
+        let mut files = self.files.clone();
+        let mut moves = vec![];
+        for file in files.iter_mut().rev().filter(|file| file.length > 0) {
+            let leftmost_fitting = free_by_length[file.length..]
+                .iter()
+                .filter_map(|heap| heap.peek().map(|Reverse(span)| *span))
+                .filter(|(start, _)| *start < file.start)
+                .min();
+
+            if let Some((start, length)) = leftmost_fitting {
+                free_by_length[bucket(length)].pop();
+                if length > file.length {
+                    let rest = length - file.length;
+                    free_by_length[bucket(rest)].push(Reverse((start + file.length, rest)));
+                }
+                moves.push(Move {
+                    file_id: file.file_id,
+                    from: file.start,
+                    to: start,
+                    length: file.length,
+                });
+                file.start = start;
+            }
+        }
+        files.sort_by_key(|file| file.start);
+
+        Compaction { files, moves }
+    }
+}
+
+/// Blocks of the disk with the given files on it, up to the end of the last file.
+fn to_blocks(files: &[FileSpan]) -> FileBlocks {
+    let end = files
+        .iter()
+        .map(|file| file.start + file.length)
+        .max()
+        .unwrap_or(0);
+    let mut blocks: FileBlocks = vec![None; end];
+    for file in files {
+        blocks[file.start..file.start + file.length].fill(Some(FileBlock(file.file_id)));
+    }
+
+    blocks
+}
+
+/// Dense disk map of the blocks, the reverse of parsing one. Files are numbered by
+/// their order on the disk, and runs too long for a single digit are split with
+/// empty files or free spaces in between, so the map reads back into the same blocks
+/// up to the file ids.
+fn to_disk_map(blocks: &FileBlocks) -> String {
+    let mut digits = vec![];
+    for (block, run) in &blocks.iter().chunk_by(|block| *block) {
+        let is_file = block.is_some();
+        if is_file != digits.len().is_multiple_of(2) {
+            digits.push(0);
+        }
+
+        let mut length = run.count();
+        while length > 9 {
+            digits.extend([9, 0]);
+            length -= 9;
+        }
+        digits.push(length);
+    }
+
+    digits
+        .into_iter()
+        .map(|digit| char::from_digit(digit as u32, 10).unwrap())
+        .collect()
+}
+
+/// Layout in the puzzle's notation, such as `00...111...2`. Only ids below 36 fit into
+/// a single character, larger ones show up as `#`.
+fn render_blocks(blocks: &FileBlocks) -> String {
+    blocks
+        .iter()
+        .map(|block| match block {
+            Some(FileBlock(file_id)) => u32::try_from(*file_id)
+                .ok()
+                .and_then(|file_id| char::from_digit(file_id, 36))
+                .unwrap_or('#'),
+            None => '.',
+        })
+        .collect()
+}
+
 fn parse_input(input: &str) -> Vec<FileMapEntry> {
     input
         .trim()
@@ -53,64 +303,6 @@ fn parse_input(input: &str) -> Vec<FileMapEntry> {
         .collect()
 }
 
-fn sort_file_blocks(file_blocks: &mut FileBlocks) {
-    let last_index = file_blocks.len() - 1;
-    let mut current_index = last_index;
-
-    while current_index > 0 {
-        let value = file_blocks[current_index].clone();
-        let (first_free_index, _) = file_blocks
-            .iter()
-            .find_position(|block| block.is_none())
-            .unwrap();
-
-        if first_free_index > current_index {
-            break;
-        }
-
-        if value.is_some() {
-            file_blocks.remove(current_index);
-
-            file_blocks.remove(first_free_index);
-            file_blocks.insert(first_free_index, value);
-            file_blocks.push(None);
-        }
-        current_index -= 1
-    }
-}
-
-fn find_free_chunk_of_size(blocks: &[Option<FileBlock>], len: usize, size: usize) -> Option<usize> {
-    (0..len).find(|i| blocks[*i..].iter().take(size).all(|x| x.is_none()))
-}
-
-fn find_next_block_chunk(blocks: &[Option<FileBlock>], start: usize) -> (usize, usize) {
-    let end_index = (0..start).rev().find(|i| blocks[*i].is_some()).unwrap();
-    let block = blocks[end_index].clone().unwrap();
-    let mut start_index = end_index;
-    while start_index > 0 && blocks[start_index - 1] == Some(block.clone()) {
-        start_index -= 1;
-    }
-
-    (start_index, end_index)
-}
-
-fn sort_part_2(file_blocks: &mut FileBlocks) {
-    let length = file_blocks.len();
-    let (mut start_index, mut end_index) = find_next_block_chunk(file_blocks, length);
-
-    while start_index > 0 {
-        let size = end_index - start_index + 1;
-        if let Some(first_free_index) = find_free_chunk_of_size(&file_blocks, length, size) {
-            if first_free_index < start_index {
-                (start_index..=end_index)
-                    .zip(first_free_index..)
-                    .for_each(|(i, j)| file_blocks.swap(i, j));
-            }
-        }
-        (start_index, end_index) = find_next_block_chunk(&file_blocks, start_index);
-    }
-}
-
 fn calculate_checksum(position: usize, block: &Option<FileBlock>) -> Option<u64> {
     if let Some(FileBlock(file_id)) = block {
         let checksum_part: u64 = file_id * (position as u64);
@@ -120,45 +312,88 @@ fn calculate_checksum(position: usize, block: &Option<FileBlock>) -> Option<u64>
     None
 }
 
+fn checksum(files: &[FileSpan]) -> u64 {
+    to_blocks(files)
+        .iter()
+        .enumerate()
+        .filter_map(|(position, block)| calculate_checksum(position, block))
+        .sum()
+}
+
 impl DaySolver for Day9Solver {
     fn solve_part1(&self, input: &str) -> Result<String, DayError> {
-        let mut blocks: FileBlocks = parse_input(input)
-            .iter()
-            .flat_map(|map_entry| map_entry.to_blocks())
-            .collect();
-        sort_file_blocks(&mut blocks);
-
-        let checksum: u64 = blocks
-            .iter()
-            .enumerate()
-            .filter_map(|(position, block)| calculate_checksum(position, block))
-            .sum();
+        let disk = Disk::new(&parse_input(input));
 
-        Ok(checksum.to_string())
+        Ok(checksum(&disk.compact_blocks().files).to_string())
     }
 
     fn solve_part2(&self, input: &str) -> Result<String, DayError> {
-        let mut blocks: FileBlocks = parse_input(input)
-            .iter()
-            .flat_map(|map_entry| map_entry.to_blocks())
-            .collect();
-        sort_part_2(&mut blocks);
+        let disk = Disk::new(&parse_input(input));
 
-        let checksum: u64 = blocks
-            .iter()
-            .enumerate()
-            .filter_map(|(position, block)| calculate_checksum(position, block))
-            .sum();
+        Ok(checksum(&disk.compact_files().files).to_string())
+    }
 
-        Ok(checksum.to_string())
+    fn visualize(&self, input: &str, visualizer: &mut dyn Visualize) -> Result<(), DayError> {
+        let disk = Disk::new(&parse_input(input));
+        let steps = [
+            (
+                "Moving blocks",
+                disk.compact_blocks()
+                    .moves
+                    .iter()
+                    .flat_map(Move::single_blocks)
+                    .collect::<Vec<_>>(),
+            ),
+            ("Moving files", disk.compact_files().moves),
+        ];
+
+        for (name, moves) in steps {
+            let mut blocks = to_blocks(&disk.files);
+            visualizer.frame(Frame::from_text(
+                format!("{name}, initial layout"),
+                &render_blocks(&blocks),
+            ))?;
+            for (step, file_move) in moves.iter().enumerate() {
+                file_move.apply(&mut blocks);
+                visualizer.frame(Frame::from_text(
+                    format!("{name}, step {}: {file_move}", step + 1),
+                    &render_blocks(&blocks),
+                ))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn report(&self, input: &str, visualizer: &mut dyn Visualize) -> Result<(), DayError> {
+        let disk = Disk::new(&parse_input(input));
+
+        for (name, compaction) in [
+            ("Moving blocks", disk.compact_blocks()),
+            ("Moving files", disk.compact_files()),
+        ] {
+            let disk_map = to_disk_map(&to_blocks(&compaction.files));
+            let log = compaction.moves.iter().map(Move::to_string).join("\n");
+            visualizer.frame(Frame::from_text(
+                format!("{name}: {} moves", compaction.moves.len()),
+                &format!("Disk map afterwards: {disk_map}\n{log}"),
+            ))?;
+        }
+
+        Ok(())
     }
 }
+
 #[cfg(test)]
 mod tests {
     use crate::days::day09::{
-        parse_input, Day9Solver, FileBlock,  FileMapEntry,
+        checksum, parse_input, to_blocks, to_disk_map, Day9Solver, Disk, FileBlock, FileBlocks,
+        FileMapEntry, FileSpan, Move,
     };
     use crate::days::DaySolver;
+    use crate::utils::visualize::{Frame, FrameRecorder};
+    use itertools::Itertools;
+    use proptest::prelude::*;
 
     fn get_example_input() -> &'static str {
         "2333133121414131402"
@@ -191,15 +426,27 @@ mod tests {
     }
 
     #[test]
-    fn test_file_map_to_block() {
+    fn test_file_spans_to_blocks() {
         assert_eq!(
-            FileMapEntry {
-                file_id: 1,
-                file_size: 2,
-                free_space: 2
-            }
-            .to_blocks(),
-            vec![Some(FileBlock(1)), Some(FileBlock(1)), None, None]
+            to_blocks(&[
+                FileSpan {
+                    file_id: 1,
+                    start: 0,
+                    length: 2
+                },
+                FileSpan {
+                    file_id: 2,
+                    start: 4,
+                    length: 1
+                }
+            ]),
+            vec![
+                Some(FileBlock(1)),
+                Some(FileBlock(1)),
+                None,
+                None,
+                Some(FileBlock(2))
+            ]
         )
     }
 
@@ -213,4 +460,205 @@ mod tests {
         let solution = Day9Solver {}.solve_part2(get_example_input()).unwrap();
         assert_eq!(solution, "2858")
     }
+
+    fn initial_blocks(input: &str) -> FileBlocks {
+        to_blocks(&Disk::new(&parse_input(input)).files)
+    }
+
+    /// Moves the last block into the first free one, one block at a time.
+    fn compact_blocks_naively(blocks: &mut FileBlocks) {
+        while let Some(first_free) = blocks.iter().position(Option::is_none) {
+            let Some(last_used) = blocks.iter().rposition(Option::is_some) else {
+                break;
+            };
+            if first_free > last_used {
+                break;
+            }
+            blocks.swap(first_free, last_used);
+        }
+    }
+
+    /// Looks for free space from the start of the disk for every file.
+    fn compact_files_naively(blocks: &mut FileBlocks) {
+        let files = blocks
+            .iter()
+            .enumerate()
+            .filter_map(|(position, block)| Some((block.clone()?.0, position)))
+            .into_group_map();
+
+        for file_id in files.keys().sorted().rev() {
+            let (start, size) = (files[file_id][0], files[file_id].len());
+            let free = (0..start).find(|position| {
+                blocks[*position..*position + size]
+                    .iter()
+                    .all(Option::is_none)
+            });
+            if let Some(free) = free {
+                (0..size).for_each(|offset| blocks.swap(start + offset, free + offset));
+            }
+        }
+    }
+
+    fn naive_checksum(blocks: &FileBlocks) -> u64 {
+        blocks
+            .iter()
+            .enumerate()
+            .filter_map(|(position, block)| Some(position as u64 * block.clone()?.0))
+            .sum()
+    }
+
+    proptest! {
+        /// Disk maps include files and free spaces of size zero.
+        #[test]
+        fn test_compaction_matches_block_by_block_moves(input in "[0-9]{0,400}") {
+            let disk = Disk::new(&parse_input(&input));
+
+            let mut blocks = initial_blocks(&input);
+            compact_blocks_naively(&mut blocks);
+            prop_assert_eq!(
+                checksum(&disk.compact_blocks().files),
+                naive_checksum(&blocks)
+            );
+
+            let mut blocks = initial_blocks(&input);
+            compact_files_naively(&mut blocks);
+            prop_assert_eq!(
+                checksum(&disk.compact_files().files),
+                naive_checksum(&blocks)
+            );
+        }
+    }
+
+    #[test]
+    fn test_long_free_runs_between_empty_files() {
+        let input = format!("19{}{}1", "09".repeat(200), "11".repeat(200));
+        let disk = Disk::new(&parse_input(&input));
+
+        let mut blocks = initial_blocks(&input);
+        compact_files_naively(&mut blocks);
+        assert_eq!(
+            checksum(&disk.compact_files().files),
+            naive_checksum(&blocks)
+        );
+    }
+
+    #[test]
+    fn test_compacting_an_already_compact_disk() {
+        let disk = Disk::new(&parse_input("2031"));
+
+        assert_eq!(checksum(&disk.compact_blocks().files), 2 + 3 + 4);
+        assert_eq!(checksum(&disk.compact_files().files), 2 + 3 + 4);
+    }
+
+    #[test]
+    fn test_disk_map_round_trip() {
+        for input in ["12345", get_example_input(), "1010101", "151"] {
+            assert_eq!(to_disk_map(&initial_blocks(input)), input);
+        }
+
+        let blocks = vec![Some(FileBlock(0)); 12]
+            .into_iter()
+            .chain(vec![None; 11])
+            .chain([Some(FileBlock(1))])
+            .collect::<FileBlocks>();
+        assert_eq!(to_disk_map(&blocks), "9039021");
+        assert_eq!(
+            initial_blocks(&to_disk_map(&blocks))
+                .iter()
+                .map(Option::is_some)
+                .collect::<Vec<_>>(),
+            blocks.iter().map(Option::is_some).collect::<Vec<_>>()
+        );
+
+        let disk = Disk::new(&parse_input(get_example_input()));
+        assert_eq!(
+            to_disk_map(&to_blocks(&disk.compact_files().files)),
+            "2020103031213441454"
+        );
+    }
+
+    #[test]
+    fn test_move_log() {
+        let disk = Disk::new(&parse_input(get_example_input()));
+        let moves = disk.compact_files().moves;
+
+        assert_eq!(
+            moves[..2],
+            [
+                Move {
+                    file_id: 9,
+                    from: 40,
+                    to: 2,
+                    length: 2
+                },
+                Move {
+                    file_id: 7,
+                    from: 32,
+                    to: 8,
+                    length: 3
+                }
+            ]
+        );
+        assert_eq!(moves[0].to_string(), "file 9: blocks 40..42 moved to 2..4");
+    }
+
+    #[test]
+    fn test_rendering_steps_like_the_puzzle() {
+        let mut recorder = FrameRecorder::default();
+        Day9Solver {}.visualize("12345", &mut recorder).unwrap();
+        assert_eq!(
+            recorder
+                .frames
+                .iter()
+                .take(7)
+                .map(Frame::to_text)
+                .collect::<Vec<_>>(),
+            vec![
+                "0..111....22222",
+                "02.111....2222.",
+                "022111....222..",
+                "0221112...22...",
+                "02211122..2....",
+                "022111222......",
+                "0..111....22222",
+            ]
+        );
+
+        let mut recorder = FrameRecorder::default();
+        Day9Solver {}
+            .visualize(get_example_input(), &mut recorder)
+            .unwrap();
+        let moving_files = recorder
+            .frames
+            .iter()
+            .skip_while(|frame| !frame.title.starts_with("Moving files"))
+            .map(Frame::to_text)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            moving_files,
+            vec![
+                "00...111...2...333.44.5555.6666.777.888899",
+                "0099.111...2...333.44.5555.6666.777.8888..",
+                "0099.1117772...333.44.5555.6666.....8888..",
+                "0099.111777244.333....5555.6666.....8888..",
+                "00992111777.44.333....5555.6666.....8888..",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_report() {
+        let mut recorder = FrameRecorder::default();
+        Day9Solver {}.report("12345", &mut recorder).unwrap();
+
+        assert_eq!(recorder.frames[0].title, "Moving blocks: 2 moves");
+        assert_eq!(
+            recorder.frames[0].to_text(),
+            "\
+Disk map afterwards: 1020303
+file 2: blocks 13..15 moved to 1..3
+file 2: blocks 10..13 moved to 6..9"
+        );
+        assert_eq!(recorder.frames[1].title, "Moving files: 0 moves");
+    }
 }
@@ -1,8 +1,8 @@
 use crate::days::{DayError, DaySolver};
 use crate::utils::direction::Direction4Way;
 use crate::utils::grid::Grid;
+use crate::utils::memo::{Memo, MemoReport};
 use crate::utils::point::Point;
-use std::str::FromStr;
 use itertools::Itertools;
 
 pub struct Day10Solver;
@@ -44,8 +44,8 @@ fn parse_input(input: &str) -> (HeightMap, Vec<Point>) {
 }
 
 fn get_trails_with_reachable_end(position: &Point, height_map: &HeightMap, current_height: u32) -> Vec<Point> {
-    if (current_height == 9) {
-        return return vec![position.clone()];
+    if current_height == 9 {
+        return vec![*position];
     }
 
     let next_valid_height = current_height + 1;
@@ -69,6 +69,31 @@ fn get_trails_with_reachable_end(position: &Point, height_map: &HeightMap, curre
         }).collect::<Vec<_>>()
 }
 
+/// Amount of distinct trails from the position to any height 9. Trails merge a lot, so
+/// the counts are shared through the memo.
+fn count_trails(memo: &mut Memo<Point, usize>, position: Point, height_map: &HeightMap) -> usize {
+    memo.call(position, |memo, position| {
+        let Some(HeightMapNode(current_height)) = height_map.get_point(position) else {
+            return 0;
+        };
+        if *current_height == 9 {
+            return 1;
+        }
+
+        Direction4Way::all_directions_turning_right(Direction4Way::Right)
+            .iter()
+            .map(|direction| *position + Point::from(direction))
+            .filter(|next_position| {
+                matches!(
+                    height_map.get_point(next_position),
+                    Some(HeightMapNode(height)) if *height == current_height + 1
+                )
+            })
+            .map(|next_position| count_trails(memo, next_position, height_map))
+            .sum()
+    })
+}
+
 impl DaySolver for Day10Solver {
     fn solve_part1(&self, input: &str) -> Result<String, DayError> {
         let (height_map, starting_positions) = parse_input(input);
@@ -76,8 +101,10 @@ impl DaySolver for Day10Solver {
         let trailhead_scores: usize = starting_positions
             .iter()
             .map(|position| {
-                let score = get_trails_with_reachable_end(position, &height_map, 0).iter().unique().count();
-                return score;
+                get_trails_with_reachable_end(position, &height_map, 0)
+                    .iter()
+                    .unique()
+                    .count()
             })
             .sum();
 
@@ -85,15 +112,22 @@ impl DaySolver for Day10Solver {
     }
 
     fn solve_part2(&self, input: &str) -> Result<String, DayError> {
+        self.solve_part2_with_memos(input, &mut MemoReport::default())
+    }
+
+    fn solve_part2_with_memos(
+        &self,
+        input: &str,
+        memos: &mut MemoReport,
+    ) -> Result<String, DayError> {
         let (height_map, starting_positions) = parse_input(input);
 
+        let mut memo = Memo::new("day10 trail ratings");
         let trailhead_scores: usize = starting_positions
             .iter()
-            .map(|position| {
-                let score = get_trails_with_reachable_end(position, &height_map, 0).iter().count();
-                return score;
-            })
+            .map(|position| count_trails(&mut memo, *position, &height_map))
             .sum();
+        memos.record(&memo);
 
         Ok(trailhead_scores.to_string())
     }
@@ -103,6 +137,7 @@ impl DaySolver for Day10Solver {
 mod tests {
     use crate::days::day10::Day10Solver;
     use crate::days::DaySolver;
+    use crate::utils::memo::MemoReport;
 
     fn get_example_input() -> &'static str {
         "89010123
@@ -121,8 +156,22 @@ mod tests {
         assert_eq!(solution, "36")
     }
 
+    #[test]
     fn test_part2() {
         let solution = Day10Solver {}.solve_part2(get_example_input()).unwrap();
         assert_eq!(solution, "81")
     }
+
+    #[test]
+    fn test_part2_records_its_memo() {
+        let mut memos = MemoReport::default();
+        let solution = Day10Solver {}
+            .solve_part2_with_memos(get_example_input(), &mut memos)
+            .unwrap();
+
+        assert_eq!(solution, "81");
+        assert_eq!(memos.memos.len(), 1);
+        assert_eq!(memos.memos[0].0, "day10 trail ratings");
+        assert!(memos.memos[0].1.hits > 0);
+    }
 }
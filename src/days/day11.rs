@@ -1,41 +1,65 @@
-use std::collections::HashMap;
 use crate::days::{DayError, DaySolver};
+use crate::utils::counter::Counter;
+use crate::utils::math::{num_digits, split_digits_at};
+use crate::utils::visualize::{Frame, Visualize};
 
 pub struct Day11Solver;
 
+const VISUALIZED_BLINKS: usize = 25;
+const FRAME_WIDTH: usize = 80;
+const MAX_FRAME_LINES: usize = 20;
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Stone(u64);
 
 impl Stone {
     fn is_even_digits(&self) -> bool {
-        self.0.to_string().chars().count() % 2 == 0
+        num_digits(self.0).is_multiple_of(2)
     }
 
     fn split(&self) -> Vec<Stone> {
-        let digit_as_string = self.0.to_string();
-        let digit_length = digit_as_string.len();
-
-        let (first_half, second_half) = digit_as_string.split_at(digit_length / 2);
-        let second_half_without_leading_zeroes = second_half.trim_start_matches("0");
-
-        let second_stone = match second_half_without_leading_zeroes {
-            c if c.is_empty() => Stone(0),
-            c => Stone(c.parse().unwrap()),
-        };
+        let (first_half, second_half) = split_digits_at(self.0, num_digits(self.0) / 2);
 
-        let first_stone = Stone(first_half.parse().unwrap());
-        vec![first_stone, second_stone]
+        vec![Stone(first_half), Stone(second_half)]
     }
 
     fn blink(&self) -> Vec<Stone> {
         match self {
             Stone(0) => vec![Stone(1)],
-            Stone(_) if self.is_even_digits() => self.split(),
+            _ if self.is_even_digits() => self.split(),
             Stone(d) => vec![Stone(d * 2024)],
         }
     }
 }
 
+fn blink_all(stones: &[Stone]) -> Vec<Stone> {
+    stones.iter().flat_map(|stone| stone.blink()).collect()
+}
+
+/// Stones wrapped into lines, cut off once the row gets too long to follow.
+fn stones_to_frame(title: String, stones: &[Stone]) -> Frame {
+    let mut lines: Vec<String> = vec![String::new()];
+    for stone in stones {
+        let current_line = lines.last_mut().unwrap();
+        let number = stone.0.to_string();
+
+        if !current_line.is_empty() && current_line.len() + number.len() + 1 > FRAME_WIDTH {
+            if lines.len() == MAX_FRAME_LINES {
+                lines.push("...".to_string());
+                break;
+            }
+            lines.push(number);
+        } else {
+            if !current_line.is_empty() {
+                current_line.push(' ');
+            }
+            current_line.push_str(&number);
+        }
+    }
+
+    Frame::from_text(title, &lines.join("\n"))
+}
+
 fn parse_input(input: &str) -> Vec<Stone> {
     input.trim()
         .split(" ")
@@ -47,55 +71,45 @@ impl DaySolver for Day11Solver {
     fn solve_part1(&self, input: &str) -> Result<String, DayError> {
         let mut stones = parse_input(input);
 
-        for i in 0..25 {
-            let mut new_stones = Vec::new();
-            for (index, stone) in stones.iter().enumerate() {
-                let changed_stones = stone.blink();
-                match changed_stones.as_slice() {
-                    [stone] => new_stones.push(stone.clone()),
-                    [stone1, stone2] => {
-                        new_stones.push(stone1.clone());
-                        new_stones.push(stone2.clone())
-                    }
-                    &[] | &[_, _, _, ..] => todo!()
-                }
-            }
-            stones = new_stones;
+        for _ in 0..25 {
+            stones = blink_all(&stones);
         }
 
         Ok(stones.len().to_string())
     }
 
     fn solve_part2(&self, input: &str) -> Result<String, DayError> {
-        let mut stones = parse_input(input);
-        let mut stone_counts: HashMap<Stone, u64> = HashMap::new();
-        for stone in stones {
-           *stone_counts.entry(stone).or_default() += 1;
-        }
+        let mut stone_counts: Counter<Stone> = parse_input(input).into_iter().collect();
 
-        for i in 0..75 {
-            let mut new_stone_counts: HashMap<Stone, u64> = HashMap::new();
-            for (stone, count) in &stone_counts{
-                let changed_stones = stone.blink();
-                match changed_stones.as_slice() {
-                    [stone] => *new_stone_counts.entry(stone.clone()).or_default() += count,
-                    [stone1, stone2] => {
-                        *new_stone_counts.entry(stone1.clone()).or_default() += count;
-                        *new_stone_counts.entry(stone2.clone()).or_default() += count;
-                    }
-                    &[] | &[_, _, _, ..] => todo!()
-                }
+        for _ in 0..75 {
+            let mut new_stone_counts = Counter::new();
+            for (stone, count) in stone_counts.iter() {
+                new_stone_counts.extend(stone.blink().into_iter().map(|stone| (stone, count)));
             }
             stone_counts = new_stone_counts;
         }
 
-        Ok(stone_counts.values().sum::<u64>().to_string())
+        Ok(stone_counts.total().to_string())
+    }
+
+    fn visualize(&self, input: &str, visualizer: &mut dyn Visualize) -> Result<(), DayError> {
+        let mut stones = parse_input(input);
+        visualizer.frame(stones_to_frame("Initial arrangement".to_string(), &stones))?;
+
+        for blink in 1..=VISUALIZED_BLINKS {
+            stones = blink_all(&stones);
+            let title = format!("After {blink} blinks: {} stones", stones.len());
+            visualizer.frame(stones_to_frame(title, &stones))?;
+        }
+
+        Ok(())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::utils::visualize::FrameRecorder;
 
     fn get_example_input() -> &'static str {
         "125 17"
@@ -106,4 +120,17 @@ mod tests {
         let solution = Day11Solver {}.solve_part1(get_example_input()).unwrap();
         assert_eq!(solution, "55312");
     }
+
+    #[test]
+    fn test_visualizing_blinks() {
+        let mut recorder = FrameRecorder::default();
+        Day11Solver {}.visualize(get_example_input(), &mut recorder).unwrap();
+
+        assert_eq!(recorder.frames.len(), 26);
+        assert_eq!(recorder.frames[1].title, "After 1 blinks: 3 stones");
+        assert_eq!(recorder.frames[2].to_text(), "253 0 2024 14168");
+        assert_eq!(recorder.frames[25].title, "After 25 blinks: 55312 stones");
+        assert_eq!(recorder.frames[25].rows.len(), 21);
+        assert!(recorder.frames[25].rows.iter().all(|row| row.len() <= 80));
+    }
 }
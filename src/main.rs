@@ -2,9 +2,14 @@ mod days;
 mod utils;
 
 use std::error::Error;
+use std::io;
+use std::time::{Duration, Instant};
 use clap::{Args, Parser, Subcommand};
-use crate::days::{Day, DaySolver};
+use crate::days::{Day, DayError, DaySolver};
+use crate::utils::memo::{MemoReport, MemoStats};
 use crate::utils::read_input_file;
+use crate::utils::render::Palette;
+use crate::utils::visualize::{FrameDump, FrameFormat, TerminalPlayer, Visualize};
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
@@ -36,6 +41,24 @@ struct SolveArgs {
     /// Input file path for solving the problem
     #[arg(short, long)]
     input_file_path: String,
+    /// Play the intermediate states of the solution before solving
+    #[arg(long)]
+    visualize: bool,
+    /// Show the structured results of the solution drawn onto the input before solving
+    #[arg(long)]
+    report: bool,
+    /// Delay between the visualized frames in milliseconds
+    #[arg(long, default_value_t = 100)]
+    frame_delay_ms: u64,
+    /// Write the visualized frames into this directory instead of the terminal
+    #[arg(long)]
+    frames_dir: Option<String>,
+    /// File format of the frames written into the frames directory
+    #[arg(long, value_enum, default_value_t = FrameFormat::Text)]
+    frame_format: FrameFormat,
+    /// Print how long each part took along with the memoisation statistics
+    #[arg(long)]
+    time: bool,
 }
 
 
@@ -44,12 +67,97 @@ struct Solution(
     String,
 );
 
+struct PartTiming {
+    elapsed: Duration,
+    memos: Vec<(&'static str, MemoStats)>,
+}
+
+fn timed<T>(solve: impl FnOnce(&mut MemoReport) -> T) -> (T, PartTiming) {
+    let mut report = MemoReport::default();
+    let start = Instant::now();
+    let result = solve(&mut report);
+    let elapsed = start.elapsed();
+
+    (result, PartTiming { elapsed, memos: report.memos })
+}
+
+fn print_timing(part: &str, timing: &PartTiming) {
+    println!("{} took {:?}", part, timing.elapsed);
+    for (name, stats) in &timing.memos {
+        println!("  memo {}: {}", name, stats);
+    }
+}
+
+fn frame_palette() -> Palette {
+    Palette::new([200, 200, 200])
+        .with('.', [90, 90, 90])
+        .with('#', [240, 240, 240])
+        .with('X', [250, 200, 40])
+        .with('O', [80, 200, 120])
+        .with('+', [80, 160, 230])
+        .with('|', [80, 160, 230])
+        .with('-', [80, 160, 230])
+        .with('^', [230, 60, 60])
+        .with('>', [230, 60, 60])
+        .with('v', [230, 60, 60])
+        .with('<', [230, 60, 60])
+}
+
+fn create_visualizer(args: &SolveArgs) -> Result<Box<dyn Visualize>, Box<dyn Error>> {
+    let visualizer: Box<dyn Visualize> = match &args.frames_dir {
+        Some(directory) => Box::new(FrameDump::create(directory, args.frame_format, frame_palette(), 8)?),
+        None => Box::new(TerminalPlayer::new(
+            io::stdout(),
+            frame_palette(),
+            Duration::from_millis(args.frame_delay_ms),
+        )),
+    };
+
+    Ok(visualizer)
+}
+
+/// Visualization and report share the visualizer, so that frames dumped into a
+/// directory keep numbering on instead of overwriting each other.
+fn emit_frames(
+    visualize: bool,
+    report: bool,
+    solver: &dyn DaySolver,
+    input: &str,
+    visualizer: &mut dyn Visualize,
+) -> Result<(), DayError> {
+    if visualize {
+        solver.visualize(input, visualizer)?;
+    }
+    if report {
+        solver.report(input, visualizer)?;
+    }
+
+    Ok(())
+}
+
 fn execute_solve(args: &SolveArgs) -> Result<Solution, Box<dyn Error>> {
-    let input = read_input_file(&args.input_file_path)?;
+    let input_file = read_input_file(&args.input_file_path)?;
+    for warning in &input_file.warnings {
+        eprintln!("Warning: {}", warning);
+    }
+    let input = input_file.content;
     let day: Day = args.day_number.try_into()?;
     let solver: Box<dyn DaySolver> = day.try_into()?;
 
-    Ok(Solution(solver.solve_part1(&input)?, solver.solve_part2(&input)?))
+    if args.visualize || args.report {
+        let mut visualizer = create_visualizer(args)?;
+        emit_frames(args.visualize, args.report, solver.as_ref(), &input, visualizer.as_mut())?;
+    }
+
+    let (part1, part1_timing) = timed(|memos| solver.solve_part1_with_memos(&input, memos));
+    let (part2, part2_timing) = timed(|memos| solver.solve_part2_with_memos(&input, memos));
+
+    if args.time {
+        print_timing("Part1", &part1_timing);
+        print_timing("Part2", &part2_timing);
+    }
+
+    Ok(Solution(part1?, part2?))
 }
 
 fn main() {
@@ -74,3 +182,37 @@ fn main() {
     }
 
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::days::{Day, DaySolver};
+    use crate::emit_frames;
+    use crate::utils::render::Palette;
+    use crate::utils::visualize::{FrameDump, FrameFormat};
+    use std::fs;
+
+    #[test]
+    fn test_visualization_and_report_share_the_frames_directory() {
+        let directory = std::env::temp_dir().join(format!("aoc-both-modes-{}", std::process::id()));
+        let day: Day = 9.try_into().unwrap();
+        let solver: Box<dyn DaySolver> = day.try_into().unwrap();
+        let mut dump =
+            FrameDump::create(&directory, FrameFormat::Text, Palette::new([0, 0, 0]), 1).unwrap();
+
+        emit_frames(true, true, solver.as_ref(), "12345", &mut dump).unwrap();
+
+        let mut files = fs::read_dir(&directory)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect::<Vec<_>>();
+        files.sort();
+        let frame = |index: usize| fs::read_to_string(directory.join(&files[index])).unwrap();
+
+        assert_eq!(files.len(), 9);
+        assert!(frame(0).starts_with("Moving blocks, initial layout"));
+        assert!(frame(7).starts_with("Moving blocks: 2 moves"));
+        assert!(frame(8).starts_with("Moving files: 0 moves"));
+
+        fs::remove_dir_all(directory).unwrap();
+    }
+}
@@ -1,7 +1,29 @@
 pub mod point;
+pub mod point3;
+pub mod counter;
+pub mod dependency_graph;
+pub mod bitset;
 pub mod direction;
 pub mod grid;
+pub mod math;
+pub mod memo;
+pub mod parse;
+pub mod rect;
+pub mod render;
+pub mod visualize;
+pub mod word_search;
 
+// Not used by any solver yet, only by their own tests.
+#[allow(dead_code)]
+pub mod cycle;
+#[allow(dead_code)]
+pub mod grid_slice;
+#[allow(dead_code)]
+pub mod ranges;
+#[allow(dead_code)]
+pub mod sparse_grid;
+
+use std::fmt::{Display, Formatter};
 use std::{fs, io};
 use thiserror::Error;
 
@@ -13,10 +35,126 @@ pub enum InputFileError {
     FileSystemOperationFailed(#[from] io::Error),
 }
 
-pub fn read_input_file(file_path: &str) -> Result<String, InputFileError> {
+/// Characters in the input that usually mean it was mangled while copying it around.
+#[derive(Debug, PartialEq)]
+pub enum InputWarning {
+    NonAscii { line: usize, column: usize, count: usize },
+    Tab { line: usize, column: usize, count: usize },
+}
+
+impl Display for InputWarning {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InputWarning::NonAscii { line, column, count } => write!(
+                f,
+                "Input contains {count} non-ASCII characters, the first at line {line}, column {column}."
+            ),
+            InputWarning::Tab { line, column, count } => write!(
+                f,
+                "Input contains {count} tab characters, the first at line {line}, column {column}."
+            ),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct InputFile {
+    pub content: String,
+    pub warnings: Vec<InputWarning>,
+}
+
+/// Converts `\r\n` and `\r` line endings into `\n`, strips trailing whitespace from
+/// every line and drops the trailing newlines, so solvers only ever see one layout.
+pub fn normalize_input(raw: &str) -> InputFile {
+    let raw_lines = raw.replace("\r\n", "\n").replace('\r', "\n");
+    let content = raw_lines
+        .lines()
+        .map(str::trim_end)
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim_end_matches('\n')
+        .to_string();
+
+    // Looked up before trimming, so that trailing tabs are reported as well.
+    let positions_of = |predicate: fn(&char) -> bool| {
+        raw_lines
+            .lines()
+            .enumerate()
+            .flat_map(|(y, line)| {
+                line.chars()
+                    .enumerate()
+                    .filter(move |(_, char)| predicate(char))
+                    .map(move |(x, _)| (y + 1, x + 1))
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let mut warnings = vec![];
+    let non_ascii = positions_of(|char| !char.is_ascii());
+    if let Some((line, column)) = non_ascii.first() {
+        warnings.push(InputWarning::NonAscii {
+            line: *line,
+            column: *column,
+            count: non_ascii.len(),
+        });
+    }
+    let tabs = positions_of(|char| *char == '\t');
+    if let Some((line, column)) = tabs.first() {
+        warnings.push(InputWarning::Tab {
+            line: *line,
+            column: *column,
+            count: tabs.len(),
+        });
+    }
+
+    InputFile { content, warnings }
+}
+
+pub fn read_input_file(file_path: &str) -> Result<InputFile, InputFileError> {
     if !fs::exists(file_path)? {
         return Err(InputFileError::InputDoesNotExists(file_path.to_owned()));
     }
 
-    Ok(fs::read_to_string(file_path)?)
+    Ok(normalize_input(&fs::read_to_string(file_path)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::{normalize_input, InputWarning};
+
+    #[test]
+    fn test_normalize_line_endings_and_trailing_whitespace() {
+        let input = normalize_input("1   2  \r\n3   4\r\n\r\n5,6\r7\n\n\n");
+
+        assert_eq!(input.content, "1   2\n3   4\n\n5,6\n7");
+        assert!(input.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_warns_about_tabs_and_non_ascii() {
+        let input = normalize_input("ab\tc\n\u{e9}\t\u{2013}");
+
+        assert_eq!(
+            input.warnings,
+            vec![
+                InputWarning::NonAscii { line: 2, column: 1, count: 2 },
+                InputWarning::Tab { line: 1, column: 3, count: 2 },
+            ]
+        );
+        assert_eq!(
+            input.warnings[1].to_string(),
+            "Input contains 2 tab characters, the first at line 1, column 3."
+        );
+    }
+
+    #[test]
+    fn test_warns_about_trailing_tabs() {
+        let input = normalize_input("1\t2\t\n3\t\n");
+
+        assert_eq!(input.content, "1\t2\n3");
+        assert_eq!(
+            input.warnings,
+            vec![InputWarning::Tab { line: 1, column: 2, count: 3 }]
+        );
+    }
 }
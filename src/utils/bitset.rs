@@ -0,0 +1,96 @@
+/// Fixed size set of indices stored as one bit each.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitSet {
+    words: Vec<u64>,
+    capacity: usize,
+}
+
+impl BitSet {
+    pub fn new(capacity: usize) -> BitSet {
+        BitSet {
+            words: vec![0; capacity.div_ceil(64)],
+            capacity,
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns whether the index was newly added. Panics when the index is out of capacity.
+    pub fn insert(&mut self, index: usize) -> bool {
+        assert!(
+            index < self.capacity,
+            "index {index} out of capacity {}",
+            self.capacity
+        );
+        let (word, mask) = (index / 64, 1 << (index % 64));
+        let is_new = self.words[word] & mask == 0;
+        self.words[word] |= mask;
+
+        is_new
+    }
+
+    pub fn remove(&mut self, index: usize) {
+        if index < self.capacity {
+            self.words[index / 64] &= !(1 << (index % 64));
+        }
+    }
+
+    pub fn contains(&self, index: usize) -> bool {
+        index < self.capacity && self.words[index / 64] & (1 << (index % 64)) != 0
+    }
+
+    /// Amount of indices in the set.
+    pub fn len(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|word| *word == 0)
+    }
+
+    #[allow(dead_code)]
+    pub fn clear(&mut self) {
+        self.words.fill(0);
+    }
+
+    #[allow(dead_code)]
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(index, word)| {
+            (0..64)
+                .filter(move |bit| word & (1 << bit) != 0)
+                .map(move |bit| index * 64 + bit)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::bitset::BitSet;
+
+    #[test]
+    fn test_bitset() {
+        let mut set = BitSet::new(130);
+
+        assert!(set.insert(0));
+        assert!(set.insert(129));
+        assert!(!set.insert(129));
+        assert!(set.insert(64));
+        assert!(set.contains(64));
+        assert!(!set.contains(63));
+        assert!(!set.contains(500));
+        assert_eq!(set.len(), 3);
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![0, 64, 129]);
+
+        set.remove(64);
+        assert_eq!(set.len(), 2);
+        set.clear();
+        assert!(set.is_empty());
+    }
+}
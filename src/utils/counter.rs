@@ -0,0 +1,144 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Multiset counting how many times every key has been added.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Counter<K: Hash + Eq> {
+    counts: HashMap<K, u64>,
+}
+
+impl<K: Hash + Eq> Default for Counter<K> {
+    fn default() -> Self {
+        Counter {
+            counts: HashMap::new(),
+        }
+    }
+}
+
+impl<K: Hash + Eq> Counter<K> {
+    pub fn new() -> Counter<K> {
+        Counter::default()
+    }
+
+    pub fn add(&mut self, key: K) {
+        self.add_n(key, 1);
+    }
+
+    pub fn add_n(&mut self, key: K, n: u64) {
+        if n > 0 {
+            *self.counts.entry(key).or_default() += n;
+        }
+    }
+
+    /// Count of the key, 0 for keys never added.
+    pub fn get(&self, key: &K) -> u64 {
+        self.counts.get(key).copied().unwrap_or(0)
+    }
+
+    /// Sum of all counts.
+    pub fn total(&self) -> u64 {
+        self.counts.values().sum()
+    }
+
+    /// Amount of distinct keys.
+    #[allow(dead_code)]
+    pub fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&K, u64)> {
+        self.counts.iter().map(|(key, count)| (key, *count))
+    }
+
+    #[allow(dead_code)]
+    pub fn merge(&mut self, other: Counter<K>) {
+        other
+            .counts
+            .into_iter()
+            .for_each(|(key, count)| self.add_n(key, count));
+    }
+
+    /// The `n` keys with the highest counts, ties broken by the key order.
+    #[allow(dead_code)]
+    pub fn most_common(&self, n: usize) -> Vec<(&K, u64)>
+    where
+        K: Ord,
+    {
+        let mut entries = self.iter().collect::<Vec<_>>();
+        entries.sort_by(|(a_key, a_count), (b_key, b_count)| {
+            b_count.cmp(a_count).then_with(|| a_key.cmp(b_key))
+        });
+        entries.truncate(n);
+
+        entries
+    }
+}
+
+impl<K: Hash + Eq> IntoIterator for Counter<K> {
+    type Item = (K, u64);
+    type IntoIter = std::collections::hash_map::IntoIter<K, u64>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.counts.into_iter()
+    }
+}
+
+impl<K: Hash + Eq> FromIterator<K> for Counter<K> {
+    fn from_iter<I: IntoIterator<Item = K>>(iter: I) -> Self {
+        let mut counter = Counter::new();
+        counter.extend(iter);
+
+        counter
+    }
+}
+
+impl<K: Hash + Eq> Extend<K> for Counter<K> {
+    fn extend<I: IntoIterator<Item = K>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|key| self.add(key));
+    }
+}
+
+impl<K: Hash + Eq> Extend<(K, u64)> for Counter<K> {
+    fn extend<I: IntoIterator<Item = (K, u64)>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|(key, n)| self.add_n(key, n));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::counter::Counter;
+
+    #[test]
+    fn test_counting() {
+        let mut counter: Counter<char> = "abracadabra".chars().collect();
+        counter.add('z');
+        counter.add_n('b', 3);
+        counter.add_n('q', 0);
+
+        assert_eq!(counter.get(&'a'), 5);
+        assert_eq!(counter.get(&'b'), 5);
+        assert_eq!(counter.get(&'q'), 0);
+        assert_eq!(counter.total(), 15);
+        assert_eq!(counter.len(), 6);
+        assert_eq!(
+            counter.most_common(3),
+            vec![(&'a', 5), (&'b', 5), (&'r', 2)]
+        );
+    }
+
+    #[test]
+    fn test_merge_and_extend() {
+        let mut counter: Counter<i32> = [1, 2, 2].into_iter().collect();
+        counter.merge([2, 3].into_iter().collect());
+        counter.extend([(3, 10), (4, 1)]);
+
+        let mut counts = counter.into_iter().collect::<Vec<_>>();
+        counts.sort();
+        assert_eq!(counts, vec![(1, 1), (2, 3), (3, 11), (4, 1)]);
+    }
+}
@@ -0,0 +1,194 @@
+//! Cycle detection for simulations that repeat a step function on a state. A step
+//! function returns `None` once the simulation ends, in which case there is no cycle.
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// States from step `start` onwards repeat every `length` steps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: usize,
+    pub length: usize,
+}
+
+impl Cycle {
+    /// Earliest step that has the same state as step `n`.
+    pub fn reduce(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.length
+        }
+    }
+}
+
+/// Floyd's tortoise and hare, needing memory for only two states.
+pub fn floyd<S: Clone + Eq>(initial: S, mut step: impl FnMut(&S) -> Option<S>) -> Option<Cycle> {
+    let mut tortoise = step(&initial)?;
+    let mut hare = step(&tortoise)?;
+    while tortoise != hare {
+        tortoise = step(&tortoise)?;
+        hare = step(&hare)?;
+        hare = step(&hare)?;
+    }
+
+    let mut start = 0;
+    tortoise = initial;
+    while tortoise != hare {
+        tortoise = step(&tortoise)?;
+        hare = step(&hare)?;
+        start += 1;
+    }
+
+    let mut length = 1;
+    hare = step(&tortoise)?;
+    while tortoise != hare {
+        hare = step(&hare)?;
+        length += 1;
+    }
+
+    Some(Cycle { start, length })
+}
+
+/// Brent's algorithm, usually calling the step function less often than Floyd's.
+pub fn brent<S: Clone + Eq>(initial: S, mut step: impl FnMut(&S) -> Option<S>) -> Option<Cycle> {
+    let (mut power, mut length) = (1, 1);
+    let mut tortoise = initial.clone();
+    let mut hare = step(&initial)?;
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare)?;
+        length += 1;
+    }
+
+    let mut start = 0;
+    tortoise = initial.clone();
+    hare = initial;
+    for _ in 0..length {
+        hare = step(&hare)?;
+    }
+    while tortoise != hare {
+        tortoise = step(&tortoise)?;
+        hare = step(&hare)?;
+        start += 1;
+    }
+
+    Some(Cycle { start, length })
+}
+
+/// Remembers every state, finding the cycle as soon as the first state repeats.
+pub fn hashed<S: Clone + Eq + Hash>(
+    initial: S,
+    step: impl FnMut(&S) -> Option<S>,
+) -> Option<Cycle> {
+    simulate(initial, step, usize::MAX).1
+}
+
+/// State after `n` steps, skipping over whole cycles once one is found. `None` when
+/// the simulation ends before reaching step `n`.
+pub fn state_at<S: Clone + Eq + Hash>(
+    initial: S,
+    step: impl FnMut(&S) -> Option<S>,
+    n: usize,
+) -> Option<S> {
+    match simulate(initial, step, n) {
+        (mut states, Some(cycle)) => Some(states.swap_remove(cycle.reduce(n))),
+        (mut states, None) if states.len() - 1 == n => states.pop(),
+        _ => None,
+    }
+}
+
+/// States up to step `limit` or until the first repetition, along with the cycle.
+fn simulate<S: Clone + Eq + Hash>(
+    initial: S,
+    mut step: impl FnMut(&S) -> Option<S>,
+    limit: usize,
+) -> (Vec<S>, Option<Cycle>) {
+    let mut seen: HashMap<S, usize> = HashMap::from([(initial.clone(), 0)]);
+    let mut states = vec![initial];
+
+    while states.len() <= limit {
+        let Some(next) = step(states.last().unwrap()) else {
+            break;
+        };
+        if let Some(start) = seen.get(&next) {
+            let cycle = Cycle {
+                start: *start,
+                length: states.len() - start,
+            };
+            return (states, Some(cycle));
+        }
+
+        seen.insert(next.clone(), states.len());
+        states.push(next);
+    }
+
+    (states, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::cycle::{brent, floyd, hashed, state_at, Cycle};
+
+    /// 3, 10, 5, 16, 8, 4, 2, 1, 4, 2, 1, ...
+    fn collatz(n: &u64) -> Option<u64> {
+        Some(if n.is_multiple_of(2) {
+            n / 2
+        } else {
+            3 * n + 1
+        })
+    }
+
+    fn countdown(n: &u64) -> Option<u64> {
+        n.checked_sub(1)
+    }
+
+    #[test]
+    fn test_detectors_agree() {
+        let expected = Some(Cycle {
+            start: 5,
+            length: 3,
+        });
+
+        assert_eq!(floyd(3, collatz), expected);
+        assert_eq!(brent(3, collatz), expected);
+        assert_eq!(hashed(3, collatz), expected);
+
+        let fixed_point = Some(Cycle {
+            start: 0,
+            length: 1,
+        });
+        assert_eq!(floyd(7, |n| Some(*n)), fixed_point);
+        assert_eq!(brent(7, |n| Some(*n)), fixed_point);
+        assert_eq!(hashed(7, |n| Some(*n)), fixed_point);
+    }
+
+    #[test]
+    fn test_ending_simulation_has_no_cycle() {
+        assert_eq!(floyd(10, countdown), None);
+        assert_eq!(brent(10, countdown), None);
+        assert_eq!(hashed(10, countdown), None);
+    }
+
+    #[test]
+    fn test_state_at() {
+        assert_eq!(state_at(3, collatz, 0), Some(3));
+        assert_eq!(state_at(3, collatz, 4), Some(8));
+        assert_eq!(state_at(3, collatz, 1_000_000_000), Some(1));
+        assert_eq!(state_at(10, countdown, 10), Some(0));
+        assert_eq!(state_at(10, countdown, 11), None);
+        assert_eq!(state_at(10, countdown, usize::MAX), None);
+        assert_eq!(state_at(3, collatz, usize::MAX), Some(2));
+        assert_eq!(
+            Cycle {
+                start: 5,
+                length: 3
+            }
+            .reduce(9),
+            6
+        );
+    }
+}
@@ -0,0 +1,149 @@
+use itertools::Itertools;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fmt::Display;
+use std::hash::Hash;
+use thiserror::Error;
+
+/// Dependencies that loop back onto themselves, as a chain where every node has to
+/// come before the next one and the last node equals the first.
+#[derive(Debug, Error, PartialEq)]
+#[error("Dependencies form a cycle: {}", .chain.iter().join(" -> "))]
+pub struct DependencyCycle<T: Display> {
+    pub chain: Vec<T>,
+}
+
+/// Directed graph of "comes before" relations.
+#[derive(Debug, Clone)]
+pub struct DependencyGraph<T> {
+    successors: HashMap<T, HashSet<T>>,
+}
+
+impl<T> Default for DependencyGraph<T> {
+    fn default() -> Self {
+        DependencyGraph {
+            successors: HashMap::new(),
+        }
+    }
+}
+
+impl<T: Copy + Eq + Hash + Display> DependencyGraph<T> {
+    pub fn new() -> DependencyGraph<T> {
+        DependencyGraph::default()
+    }
+
+    pub fn add_dependency(&mut self, before: T, after: T) {
+        self.successors.entry(before).or_default().insert(after);
+    }
+
+    /// Whether there is a direct dependency putting `before` ahead of `after`.
+    pub fn must_precede(&self, before: &T, after: &T) -> bool {
+        self.successors
+            .get(before)
+            .is_some_and(|successors| successors.contains(after))
+    }
+
+    /// Orders `nodes` so that every dependency between them holds, only looking at the
+    /// dependencies among the given nodes. Nodes that are free to go anywhere keep
+    /// their original relative order.
+    pub fn topological_sort(&self, nodes: &[T]) -> Result<Vec<T>, DependencyCycle<T>> {
+        let mut in_degrees = nodes
+            .iter()
+            .map(|node| {
+                nodes
+                    .iter()
+                    .filter(|other| self.must_precede(other, node))
+                    .count()
+            })
+            .collect::<Vec<_>>();
+        let mut ready = (0..nodes.len())
+            .filter(|index| in_degrees[*index] == 0)
+            .collect::<BTreeSet<_>>();
+        let mut sorted = Vec::with_capacity(nodes.len());
+
+        while let Some(index) = ready.pop_first() {
+            sorted.push(nodes[index]);
+
+            for (other, in_degree) in in_degrees.iter_mut().enumerate() {
+                if self.must_precede(&nodes[index], &nodes[other]) {
+                    *in_degree -= 1;
+                    if *in_degree == 0 {
+                        ready.insert(other);
+                    }
+                }
+            }
+        }
+
+        if sorted.len() < nodes.len() {
+            let remaining = nodes
+                .iter()
+                .filter(|node| !sorted.contains(node))
+                .copied()
+                .collect::<Vec<_>>();
+            return Err(self.cycle_among(&remaining));
+        }
+
+        Ok(sorted)
+    }
+
+    /// Every one of the nodes has a predecessor among them, so walking backwards
+    /// through predecessors has to run into a node it has seen before.
+    fn cycle_among(&self, nodes: &[T]) -> DependencyCycle<T> {
+        let mut walk = vec![nodes[0]];
+
+        loop {
+            let current = walk.last().unwrap();
+            let predecessor = *nodes
+                .iter()
+                .find(|node| self.must_precede(node, current))
+                .unwrap();
+
+            if let Some(start) = walk.iter().position(|node| *node == predecessor) {
+                let mut chain = walk.split_off(start);
+                chain.push(predecessor);
+                chain.reverse();
+
+                return DependencyCycle { chain };
+            }
+            walk.push(predecessor);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::dependency_graph::{DependencyCycle, DependencyGraph};
+
+    #[test]
+    fn test_topological_sort() {
+        let mut graph = DependencyGraph::new();
+        graph.add_dependency(1, 2);
+        graph.add_dependency(2, 3);
+        graph.add_dependency(4, 1);
+
+        assert_eq!(graph.topological_sort(&[3, 2, 1, 4]), Ok(vec![4, 1, 2, 3]));
+        assert_eq!(graph.topological_sort(&[9, 3, 8, 2]), Ok(vec![9, 8, 2, 3]));
+        assert_eq!(graph.topological_sort(&[]), Ok(vec![]));
+    }
+
+    #[test]
+    fn test_cycles_are_reported_with_their_chain() {
+        let mut graph = DependencyGraph::new();
+        graph.add_dependency(1, 2);
+        graph.add_dependency(2, 3);
+        graph.add_dependency(3, 1);
+        graph.add_dependency(0, 1);
+
+        let cycle = graph.topological_sort(&[0, 1, 2, 3]).unwrap_err();
+        assert_eq!(
+            cycle,
+            DependencyCycle {
+                chain: vec![1, 2, 3, 1]
+            }
+        );
+        assert_eq!(
+            cycle.to_string(),
+            "Dependencies form a cycle: 1 -> 2 -> 3 -> 1"
+        );
+        assert_eq!(graph.topological_sort(&[1, 2]), Ok(vec![1, 2]));
+    }
+}
@@ -1,4 +1,4 @@
-#[derive(Debug, Clone, PartialEq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Direction4Way {
     Up,
     Down,
@@ -9,10 +9,10 @@ pub enum Direction4Way {
 impl Direction4Way {
     pub fn all_directions_turning_right(starting_from: Direction4Way) -> Vec<Direction4Way> {
         let mut directions = Vec::new();
-        let mut current_direction = starting_from.clone();
+        let mut current_direction = starting_from;
 
         loop {
-            directions.push(current_direction.clone());
+            directions.push(current_direction);
             current_direction = current_direction.turn_90_degrees_to_right();
 
             if current_direction == starting_from {
@@ -42,10 +42,67 @@ impl Direction4Way {
     }
 }
 
+/// Compass directions including the diagonals, in clockwise order starting from `Up`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction8Way {
+    Up,
+    UpRight,
+    Right,
+    DownRight,
+    Down,
+    DownLeft,
+    Left,
+    UpLeft,
+}
+
+impl Direction8Way {
+    pub const ALL: [Direction8Way; 8] = [
+        Direction8Way::Up,
+        Direction8Way::UpRight,
+        Direction8Way::Right,
+        Direction8Way::DownRight,
+        Direction8Way::Down,
+        Direction8Way::DownLeft,
+        Direction8Way::Left,
+        Direction8Way::UpLeft,
+    ];
+
+    pub fn turn_45_degrees_to_right(&self) -> Direction8Way {
+        let index = Direction8Way::ALL.iter().position(|direction| direction == self).unwrap();
+
+        Direction8Way::ALL[(index + 1) % Direction8Way::ALL.len()]
+    }
+
+    pub fn opposite(&self) -> Direction8Way {
+        self.turn_45_degrees_to_right()
+            .turn_45_degrees_to_right()
+            .turn_45_degrees_to_right()
+            .turn_45_degrees_to_right()
+    }
+}
+
+impl From<Direction4Way> for Direction8Way {
+    fn from(value: Direction4Way) -> Self {
+        match value {
+            Direction4Way::Up => Direction8Way::Up,
+            Direction4Way::Down => Direction8Way::Down,
+            Direction4Way::Left => Direction8Way::Left,
+            Direction4Way::Right => Direction8Way::Right,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_8_way_turns() {
+        assert_eq!(Direction8Way::UpLeft.turn_45_degrees_to_right(), Direction8Way::Up);
+        assert_eq!(Direction8Way::DownRight.opposite(), Direction8Way::UpLeft);
+        assert_eq!(Direction8Way::from(Direction4Way::Left), Direction8Way::Left);
+    }
+
     #[test]
     fn test_all_directions() {
         assert_eq!(
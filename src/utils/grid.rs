@@ -1,5 +1,56 @@
+use crate::utils::direction::Direction4Way;
 use crate::utils::point::Point;
+use crate::utils::rect::Rect;
 
+/// Read access shared by the dense `Grid` and the `SparseGrid`.
+pub trait GridView<T> {
+    fn get_point(&self, point: &Point) -> Option<&T>;
+
+    /// Every stored cell with its position.
+    fn points<'a>(&'a self) -> impl Iterator<Item = (Point, &'a T)>
+    where
+        T: 'a;
+
+    /// Smallest rectangle containing every stored cell, `None` when there are none.
+    fn bounds(&self) -> Option<Rect>;
+
+    /// Stored cells directly up, right, down and left of the point.
+    fn neighbours<'a>(&'a self, point: &Point) -> impl Iterator<Item = (Point, &'a T)>
+    where
+        T: 'a,
+    {
+        let point = *point;
+
+        Direction4Way::all_directions_turning_right(Direction4Way::Up)
+            .into_iter()
+            .filter_map(move |direction| {
+                let neighbour = point + Point::screen_step(&direction);
+                self.get_point(&neighbour).map(|value| (neighbour, value))
+            })
+    }
+
+    /// Stored cells in the 3x3 square around the point, excluding the point itself.
+    fn neighbours_with_diagonals<'a>(
+        &'a self,
+        point: &Point,
+    ) -> impl Iterator<Item = (Point, &'a T)>
+    where
+        T: 'a,
+    {
+        let point = *point;
+        let corner = Point { x: 1, y: 1 };
+
+        Rect {
+            min: point - corner,
+            max: point + corner,
+        }
+        .points()
+        .filter(move |neighbour| *neighbour != point)
+        .filter_map(move |neighbour| self.get_point(&neighbour).map(|value| (neighbour, value)))
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct Grid<T> {
     pub grid: Vec<Vec<T>>,
     pub height: u64,
@@ -7,9 +58,10 @@ pub struct Grid<T> {
 }
 
 impl<T> Grid<T> {
+    /// Grid of the given rows, which is empty when there are none.
     pub fn make(grid: Vec<Vec<T>>) -> Grid<T> {
         let height = grid.len();
-        let width = grid[0].len();
+        let width = grid.first().map_or(0, Vec::len);
 
         Grid {
             grid,
@@ -23,12 +75,51 @@ impl<T> Grid<T> {
 
         row.get::<usize>(point.x.try_into().ok()?)
     }
+
+    pub fn get_point_mut(&mut self, point: &Point) -> Option<&mut T> {
+        let row: &mut Vec<T> = self.grid.get_mut::<usize>(point.y.try_into().ok()?)?;
+
+        row.get_mut::<usize>(point.x.try_into().ok()?)
+    }
+
+    pub fn points(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.grid.iter().enumerate().flat_map(|(y, row)| {
+            row.iter()
+                .enumerate()
+                .map(move |(x, value)| (Point::from_index(x, y), value))
+        })
+    }
+}
+
+impl<T> GridView<T> for Grid<T> {
+    fn get_point(&self, point: &Point) -> Option<&T> {
+        Grid::get_point(self, point)
+    }
+
+    fn points<'a>(&'a self) -> impl Iterator<Item = (Point, &'a T)>
+    where
+        T: 'a,
+    {
+        Grid::points(self)
+    }
+
+    fn bounds(&self) -> Option<Rect> {
+        if self.height == 0 || self.width == 0 {
+            return None;
+        }
+
+        Some(Rect {
+            min: Point { x: 0, y: 0 },
+            max: Point::from_index(self.width as usize - 1, self.height as usize - 1),
+        })
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::utils::grid::Grid;
+    use crate::utils::grid::{Grid, GridView};
     use crate::utils::point::Point;
+    use crate::utils::rect::Rect;
 
     #[test]
     fn test_getting_with_point() {
@@ -46,4 +137,40 @@ mod tests {
         let invalid_index_point_in_grid = grid.get_point(&invalid_index_point);
         assert_eq!(invalid_index_point_in_grid, None)
     }
+
+    #[test]
+    fn test_mutating_with_point() {
+        let mut grid = Grid::make(vec![vec!['a', 'b']]);
+
+        *grid.get_point_mut(&Point { x: 1, y: 0 }).unwrap() = 'c';
+        assert_eq!(grid.get_point_mut(&Point { x: 2, y: 0 }), None);
+
+        assert_eq!(
+            grid.points().collect::<Vec<_>>(),
+            vec![(Point { x: 0, y: 0 }, &'a'), (Point { x: 1, y: 0 }, &'c')]
+        );
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = Grid::make(vec![vec![1, 2, 3], vec![4, 5, 6]]);
+
+        assert_eq!(
+            grid.neighbours(&Point { x: 0, y: 0 }).collect::<Vec<_>>(),
+            vec![(Point { x: 1, y: 0 }, &2), (Point { x: 0, y: 1 }, &4)]
+        );
+        assert_eq!(
+            grid.neighbours_with_diagonals(&Point { x: 1, y: 1 })
+                .map(|(_, value)| *value)
+                .collect::<Vec<_>>(),
+            vec![1, 2, 3, 4, 6]
+        );
+        assert_eq!(
+            grid.bounds(),
+            Some(Rect {
+                min: Point { x: 0, y: 0 },
+                max: Point { x: 2, y: 1 }
+            })
+        );
+    }
 }
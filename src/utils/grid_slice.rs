@@ -0,0 +1,280 @@
+use crate::utils::grid::{Grid, GridView};
+use crate::utils::point::Point;
+use crate::utils::rect::Rect;
+
+/// Borrowed view of a `Grid` that is rotated, flipped, transposed or cut down without
+/// copying any cells. A view point `(x, y)` reads the source cell at
+/// `origin + x_axis * x + y_axis * y`, so transforms compose by updating those vectors.
+#[derive(Debug)]
+pub struct GridSlice<'a, T> {
+    source: &'a Grid<T>,
+    origin: Point,
+    x_axis: Point,
+    y_axis: Point,
+    width: i64,
+    height: i64,
+}
+
+impl<T> Clone for GridSlice<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for GridSlice<'_, T> {}
+
+impl<'a, T> GridSlice<'a, T> {
+    pub fn width(&self) -> i64 {
+        self.width
+    }
+
+    pub fn height(&self) -> i64 {
+        self.height
+    }
+
+    /// Position in the underlying grid of a point of this view.
+    pub fn source_point(&self, point: &Point) -> Option<Point> {
+        if !(0..self.width).contains(&point.x) || !(0..self.height).contains(&point.y) {
+            return None;
+        }
+
+        Some(self.origin + self.x_axis * point.x + self.y_axis * point.y)
+    }
+
+    pub fn rotate_cw(&self) -> GridSlice<'a, T> {
+        GridSlice {
+            origin: self.origin + self.y_axis * (self.height - 1),
+            x_axis: -self.y_axis,
+            y_axis: self.x_axis,
+            width: self.height,
+            height: self.width,
+            ..*self
+        }
+    }
+
+    pub fn transpose(&self) -> GridSlice<'a, T> {
+        GridSlice {
+            x_axis: self.y_axis,
+            y_axis: self.x_axis,
+            width: self.height,
+            height: self.width,
+            ..*self
+        }
+    }
+
+    /// Mirrors left to right.
+    pub fn flip_horizontal(&self) -> GridSlice<'a, T> {
+        GridSlice {
+            origin: self.origin + self.x_axis * (self.width - 1),
+            x_axis: -self.x_axis,
+            ..*self
+        }
+    }
+
+    /// Mirrors top to bottom.
+    pub fn flip_vertical(&self) -> GridSlice<'a, T> {
+        GridSlice {
+            origin: self.origin + self.y_axis * (self.height - 1),
+            y_axis: -self.y_axis,
+            ..*self
+        }
+    }
+
+    /// View of the rectangle given in this view's coordinates, `None` when it does not
+    /// fit inside the view.
+    pub fn sub_grid(&self, rect: &Rect) -> Option<GridSlice<'a, T>> {
+        self.source_point(&rect.min)?;
+        self.source_point(&rect.max)?;
+
+        Some(GridSlice {
+            origin: self.origin + self.x_axis * rect.min.x + self.y_axis * rect.min.y,
+            width: rect.width(),
+            height: rect.height(),
+            ..*self
+        })
+    }
+
+    /// Every `width` by `height` sub-grid, row by row from the top-left corner.
+    pub fn windows(&self, width: i64, height: i64) -> impl Iterator<Item = GridSlice<'a, T>> {
+        let slice = *self;
+
+        (0..=self.height - height).flat_map(move |y| {
+            (0..=slice.width - width).filter_map(move |x| {
+                slice.sub_grid(&Rect {
+                    min: Point { x, y },
+                    max: Point {
+                        x: x + width - 1,
+                        y: y + height - 1,
+                    },
+                })
+            })
+        })
+    }
+}
+
+impl<T: Clone> GridSlice<'_, T> {
+    /// Copies the view into an owned grid.
+    pub fn to_grid(self) -> Grid<T> {
+        Grid::make(
+            (0..self.height)
+                .map(|y| {
+                    (0..self.width)
+                        .filter_map(|x| self.get_point(&Point { x, y }).cloned())
+                        .collect()
+                })
+                .collect(),
+        )
+    }
+}
+
+impl<T> GridView<T> for GridSlice<'_, T> {
+    fn get_point(&self, point: &Point) -> Option<&T> {
+        self.source.get_point(&self.source_point(point)?)
+    }
+
+    fn points<'b>(&'b self) -> impl Iterator<Item = (Point, &'b T)>
+    where
+        T: 'b,
+    {
+        self.bounds()
+            .into_iter()
+            .flat_map(|bounds| bounds.points())
+            .filter_map(|point| self.get_point(&point).map(|value| (point, value)))
+    }
+
+    fn bounds(&self) -> Option<Rect> {
+        if self.width <= 0 || self.height <= 0 {
+            return None;
+        }
+
+        Some(Rect {
+            min: Point { x: 0, y: 0 },
+            max: Point {
+                x: self.width - 1,
+                y: self.height - 1,
+            },
+        })
+    }
+}
+
+impl<T> Grid<T> {
+    /// The whole grid as an untransformed `GridSlice`.
+    pub fn view(&self) -> GridSlice<'_, T> {
+        GridSlice {
+            source: self,
+            origin: Point { x: 0, y: 0 },
+            x_axis: Point { x: 1, y: 0 },
+            y_axis: Point { x: 0, y: 1 },
+            width: self.width as i64,
+            height: self.height as i64,
+        }
+    }
+
+    pub fn rotate_cw(&self) -> GridSlice<'_, T> {
+        self.view().rotate_cw()
+    }
+
+    pub fn transpose(&self) -> GridSlice<'_, T> {
+        self.view().transpose()
+    }
+
+    pub fn flip_horizontal(&self) -> GridSlice<'_, T> {
+        self.view().flip_horizontal()
+    }
+
+    pub fn flip_vertical(&self) -> GridSlice<'_, T> {
+        self.view().flip_vertical()
+    }
+
+    pub fn sub_grid(&self, rect: &Rect) -> Option<GridSlice<'_, T>> {
+        self.view().sub_grid(rect)
+    }
+
+    pub fn windows(&self, width: i64, height: i64) -> impl Iterator<Item = GridSlice<'_, T>> {
+        self.view().windows(width, height)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::grid::{Grid, GridView};
+    use crate::utils::point::Point;
+    use crate::utils::rect::Rect;
+
+    fn get_grid() -> Grid<char> {
+        Grid::make(vec![vec!['a', 'b', 'c'], vec!['d', 'e', 'f']])
+    }
+
+    fn rows(grid: Grid<char>) -> Vec<String> {
+        grid.grid
+            .into_iter()
+            .map(|row| row.into_iter().collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_rotate_cw() {
+        let grid = get_grid();
+
+        assert_eq!(rows(grid.rotate_cw().to_grid()), vec!["da", "eb", "fc"]);
+        assert_eq!(
+            rows(
+                grid.rotate_cw()
+                    .rotate_cw()
+                    .rotate_cw()
+                    .rotate_cw()
+                    .to_grid()
+            ),
+            rows(grid.clone())
+        );
+    }
+
+    #[test]
+    fn test_transpose_and_flips() {
+        let grid = get_grid();
+
+        assert_eq!(rows(grid.transpose().to_grid()), vec!["ad", "be", "cf"]);
+        assert_eq!(rows(grid.flip_horizontal().to_grid()), vec!["cba", "fed"]);
+        assert_eq!(rows(grid.flip_vertical().to_grid()), vec!["def", "abc"]);
+        assert_eq!(
+            rows(grid.transpose().flip_horizontal().to_grid()),
+            rows(grid.rotate_cw().to_grid())
+        );
+    }
+
+    #[test]
+    fn test_sub_grid() {
+        let grid = get_grid();
+        let rect = Rect {
+            min: Point { x: 1, y: 0 },
+            max: Point { x: 2, y: 1 },
+        };
+
+        let sub_grid = grid.sub_grid(&rect).unwrap();
+        assert_eq!(rows(sub_grid.to_grid()), vec!["bc", "ef"]);
+        assert_eq!(
+            sub_grid.source_point(&Point { x: 0, y: 1 }),
+            Some(Point { x: 1, y: 1 })
+        );
+        assert_eq!(sub_grid.get_point(&Point { x: 2, y: 0 }), None);
+
+        let outside = Rect {
+            min: Point { x: 2, y: 0 },
+            max: Point { x: 3, y: 0 },
+        };
+        assert!(grid.sub_grid(&outside).is_none());
+    }
+
+    #[test]
+    fn test_windows() {
+        let grid = get_grid();
+        let windows = grid
+            .windows(2, 2)
+            .map(|window| rows(window.to_grid()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(windows, vec![vec!["ab", "de"], vec!["bc", "ef"]]);
+        assert_eq!(grid.windows(4, 1).count(), 0);
+        assert_eq!(grid.rotate_cw().windows(2, 1).count(), 3);
+    }
+}
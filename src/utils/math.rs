@@ -0,0 +1,253 @@
+use crate::utils::point::Coordinate;
+
+/// Greatest common divisor, always non-negative. `gcd(0, 0)` is 0. `None` when the
+/// result does not fit into `T`, which only happens for `gcd(T::MIN, 0)` and
+/// `gcd(T::MIN, T::MIN)`.
+pub fn gcd<T: Coordinate>(a: T, b: T) -> Option<T> {
+    // Negative values reach as far as `T::MIN`, so the division steps cannot overflow.
+    let non_positive = |value: T| if value > T::ZERO { -value } else { value };
+    let (mut a, mut b) = (non_positive(a), non_positive(b));
+    while b != T::ZERO {
+        let remainder = if b == -T::ONE { T::ZERO } else { a % b };
+        (a, b) = (b, remainder);
+    }
+
+    a.checked_neg()
+}
+
+/// Least common multiple, `None` when it does not fit into `i64`.
+#[allow(dead_code)]
+pub fn lcm(a: i64, b: i64) -> Option<i64> {
+    if a == 0 || b == 0 {
+        return Some(0);
+    }
+
+    (a / gcd(a, b)?).checked_mul(b)?.checked_abs()
+}
+
+/// Returns `(g, x, y)` where `g = gcd(a, b)` and `a * x + b * y = g`, or `None` when a
+/// step overflows, which only operands close to `i64::MIN` can cause.
+#[allow(dead_code)]
+pub fn extended_gcd(a: i64, b: i64) -> Option<(i64, i64, i64)> {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1i64, 0i64);
+    let (mut old_y, mut y) = (0i64, 1i64);
+
+    while r != 0 {
+        let quotient = old_r.checked_div(r)?;
+        (old_r, r) = (r, old_r.checked_rem(r)?);
+        (old_x, x) = (x, old_x.checked_sub(quotient.checked_mul(x)?)?);
+        (old_y, y) = (y, old_y.checked_sub(quotient.checked_mul(y)?)?);
+    }
+
+    if old_r < 0 {
+        Some((
+            old_r.checked_neg()?,
+            old_x.checked_neg()?,
+            old_y.checked_neg()?,
+        ))
+    } else {
+        Some((old_r, old_x, old_y))
+    }
+}
+
+/// Inverse of `a` modulo `modulus` in `0..modulus`, `None` when they are not coprime.
+#[allow(dead_code)]
+pub fn mod_inverse(a: i64, modulus: i64) -> Option<i64> {
+    if modulus <= 0 {
+        return None;
+    }
+
+    match extended_gcd(a.rem_euclid(modulus), modulus)? {
+        (1, x, _) => Some(x.rem_euclid(modulus)),
+        _ => None,
+    }
+}
+
+/// Solves the system `x = residue (mod modulus)` with the chinese remainder theorem.
+/// Moduli do not have to be coprime. Returns the smallest non-negative solution and
+/// the combined modulus, or `None` when the system is inconsistent or overflows.
+#[allow(dead_code)]
+pub fn crt(congruences: &[(i64, i64)]) -> Option<(i64, i64)> {
+    congruences.iter().try_fold(
+        (0i64, 1i64),
+        |(residue, modulus), &(next_residue, next_modulus)| {
+            if next_modulus <= 0 {
+                return None;
+            }
+
+            let (g, p, _) = extended_gcd(modulus, next_modulus)?;
+            // Taken modulo the next modulus, so that the products below fit into i128.
+            let difference =
+                (next_residue as i128 - residue as i128).rem_euclid(next_modulus as i128);
+            if difference % g as i128 != 0 {
+                return None;
+            }
+
+            let combined = lcm(modulus, next_modulus)?;
+            let step = next_modulus / g;
+            let k = difference / g as i128 * p as i128 % step as i128;
+            let solution = (residue as i128 + modulus as i128 * k).rem_euclid(combined as i128);
+
+            Some((solution as i64, combined))
+        },
+    )
+}
+
+/// `base` to the power of `exponent`, `None` on overflow.
+#[allow(dead_code)]
+pub fn checked_pow(base: i64, exponent: u64) -> Option<i64> {
+    let (mut result, mut base, mut exponent) = (1i64, base, exponent);
+
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = result.checked_mul(base)?;
+        }
+        exponent >>= 1;
+        if exponent > 0 {
+            base = base.checked_mul(base)?;
+        }
+    }
+
+    Some(result)
+}
+
+/// `base` to the power of `exponent` modulo `modulus`, in `0..modulus`. `None` when the
+/// modulus is not positive.
+#[allow(dead_code)]
+pub fn mod_pow(base: i64, exponent: u64, modulus: i64) -> Option<i64> {
+    if modulus <= 0 {
+        return None;
+    }
+
+    let modulus = modulus as i128;
+    let (mut result, mut base, mut exponent) =
+        (1 % modulus, (base as i128).rem_euclid(modulus), exponent);
+
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = result * base % modulus;
+        }
+        base = base * base % modulus;
+        exponent >>= 1;
+    }
+
+    Some(result as i64)
+}
+
+/// Amount of decimal digits, 0 having a single digit.
+pub fn num_digits(number: u64) -> u32 {
+    number.checked_ilog10().unwrap_or(0) + 1
+}
+
+/// Splits off the last `position` decimal digits, so `split_digits_at(1234, 2)` is
+/// `(12, 34)`. Leading zeroes of the right part vanish as with any number.
+pub fn split_digits_at(number: u64, position: u32) -> (u64, u64) {
+    match 10u64.checked_pow(position) {
+        Some(divisor) => (number / divisor, number % divisor),
+        None => (0, number),
+    }
+}
+
+/// Appends the digits of `right` to `left`, `None` on overflow.
+pub fn concat_digits(left: u64, right: u64) -> Option<u64> {
+    left.checked_mul(10u64.checked_pow(num_digits(right))?)?
+        .checked_add(right)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::math::{
+        checked_pow, concat_digits, crt, extended_gcd, gcd, lcm, mod_inverse, mod_pow, num_digits,
+        split_digits_at,
+    };
+
+    #[test]
+    fn test_gcd_and_lcm() {
+        assert_eq!(gcd(12, 18), Some(6));
+        assert_eq!(gcd(-12, 18), Some(6));
+        assert_eq!(gcd(0, 7), Some(7));
+        assert_eq!(gcd(0, 0), Some(0));
+        assert_eq!(gcd(i64::MIN, 2), Some(2));
+        assert_eq!(gcd(i64::MIN, -1), Some(1));
+        assert_eq!(gcd(i64::MIN, 0), None);
+        assert_eq!(gcd(i64::MIN, i64::MIN), None);
+        assert_eq!(gcd::<i8>(-128, 96), Some(32));
+
+        assert_eq!(lcm(4, 6), Some(12));
+        assert_eq!(lcm(-4, 6), Some(12));
+        assert_eq!(lcm(0, 6), Some(0));
+        assert_eq!(lcm(i64::MAX, i64::MAX - 1), None);
+        assert_eq!(lcm(i64::MIN, 1), None);
+    }
+
+    #[test]
+    fn test_extended_gcd() {
+        for (a, b) in [(240, 46), (-240, 46), (0, 5), (5, 0), (17, 17)] {
+            let (g, x, y) = extended_gcd(a, b).unwrap();
+
+            assert_eq!(Some(g), gcd(a, b));
+            assert_eq!(a * x + b * y, g);
+        }
+
+        assert_eq!(extended_gcd(i64::MIN, -1), None);
+        assert_eq!(extended_gcd(i64::MIN, 0), None);
+        assert_eq!(extended_gcd(i64::MIN, 2), Some((2, 0, 1)));
+    }
+
+    #[test]
+    fn test_mod_inverse() {
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(-3, 11), Some(7));
+        assert_eq!(mod_inverse(6, 9), None);
+        assert_eq!(mod_inverse(0, 7), None);
+        assert_eq!(mod_inverse(3, 0), None);
+    }
+
+    #[test]
+    fn test_crt() {
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt(&[(2, 4), (4, 6)]), Some((10, 12)));
+        assert_eq!(crt(&[(1, 4), (2, 6)]), None);
+        assert_eq!(crt(&[]), Some((0, 1)));
+        assert_eq!(crt(&[(-1, 5)]), Some((4, 5)));
+        assert_eq!(crt(&[(0, i64::MAX), (1, i64::MAX - 1)]), None);
+        assert_eq!(crt(&[(5, 7), (i64::MIN, 3)]), Some((19, 21)));
+        assert_eq!(crt(&[(i64::MAX, 5), (i64::MIN, 3)]), Some((7, 15)));
+    }
+
+    #[test]
+    fn test_powers() {
+        assert_eq!(checked_pow(3, 4), Some(81));
+        assert_eq!(checked_pow(-2, 3), Some(-8));
+        assert_eq!(checked_pow(0, 0), Some(1));
+        assert_eq!(checked_pow(2, 62), Some(1 << 62));
+        assert_eq!(checked_pow(2, 63), None);
+        assert_eq!(checked_pow(1, u64::MAX), Some(1));
+
+        assert_eq!(mod_pow(4, 13, 497), Some(445));
+        assert_eq!(mod_pow(-2, 3, 5), Some(2));
+        assert_eq!(mod_pow(5, 0, 1), Some(0));
+        assert_eq!(mod_pow(5, 3, 0), None);
+        assert_eq!(mod_pow(5, 3, -7), None);
+    }
+
+    #[test]
+    fn test_digits() {
+        assert_eq!(num_digits(0), 1);
+        assert_eq!(num_digits(9), 1);
+        assert_eq!(num_digits(10), 2);
+        assert_eq!(num_digits(u64::MAX), 20);
+
+        assert_eq!(split_digits_at(1234, 2), (12, 34));
+        assert_eq!(split_digits_at(1000, 2), (10, 0));
+        assert_eq!(split_digits_at(7, 0), (7, 0));
+        assert_eq!(split_digits_at(u64::MAX, 20), (0, u64::MAX));
+
+        assert_eq!(concat_digits(12, 345), Some(12345));
+        assert_eq!(concat_digits(12, 0), Some(120));
+        assert_eq!(concat_digits(0, 5), Some(5));
+        assert_eq!(concat_digits(u64::MAX, 1), None);
+        assert_eq!(concat_digits(1, u64::MAX), None);
+    }
+}
@@ -0,0 +1,183 @@
+use std::collections::{HashMap, VecDeque};
+use std::fmt::{Display, Formatter};
+use std::hash::Hash;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemoStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+}
+
+impl MemoStats {
+    pub fn hit_rate(&self) -> f64 {
+        match self.hits + self.misses {
+            0 => 0.0,
+            calls => self.hits as f64 / calls as f64,
+        }
+    }
+}
+
+impl Display for MemoStats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} hits, {} misses, {} evictions ({:.1}% hit rate)",
+            self.hits,
+            self.misses,
+            self.evictions,
+            self.hit_rate() * 100.0
+        )
+    }
+}
+
+/// Cache for pure recursive functions. The computation gets the memo back so it can
+/// recurse through it:
+///
+/// ```ignore
+/// let mut memo = Memo::new("fibonacci");
+/// fn fibonacci(memo: &mut Memo<u64, u64>, n: u64) -> u64 {
+///     memo.call(n, |memo, &n| if n < 2 { n } else { fibonacci(memo, n - 1) + fibonacci(memo, n - 2) })
+/// }
+/// ```
+///
+/// Its statistics only leave through `stats` or by recording them into a `MemoReport`.
+pub struct Memo<K, V> {
+    name: &'static str,
+    cache: HashMap<K, V>,
+    insertion_order: VecDeque<K>,
+    bound: Option<usize>,
+    stats: MemoStats,
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> Memo<K, V> {
+    pub fn new(name: &'static str) -> Memo<K, V> {
+        Memo::create(name, None)
+    }
+
+    /// Keeps at most `bound` entries, evicting the oldest ones first.
+    #[allow(dead_code)]
+    pub fn with_bound(name: &'static str, bound: usize) -> Memo<K, V> {
+        Memo::create(name, Some(bound))
+    }
+
+    fn create(name: &'static str, bound: Option<usize>) -> Memo<K, V> {
+        Memo {
+            name,
+            cache: HashMap::new(),
+            insertion_order: VecDeque::new(),
+            bound,
+            stats: MemoStats::default(),
+        }
+    }
+
+    pub fn call(&mut self, key: K, compute: impl FnOnce(&mut Self, &K) -> V) -> V {
+        if let Some(value) = self.cache.get(&key) {
+            self.stats.hits += 1;
+            return value.clone();
+        }
+
+        self.stats.misses += 1;
+        let value = compute(self, &key);
+        self.insert(key, value.clone());
+
+        value
+    }
+
+    #[allow(dead_code)]
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    #[allow(dead_code)]
+    pub fn stats(&self) -> MemoStats {
+        self.stats
+    }
+
+    fn insert(&mut self, key: K, value: V) {
+        if self.bound == Some(0) {
+            return;
+        }
+
+        if self.cache.insert(key.clone(), value).is_none() {
+            self.insertion_order.push_back(key);
+        }
+
+        while self.bound.is_some_and(|bound| self.cache.len() > bound) {
+            if let Some(oldest) = self.insertion_order.pop_front() {
+                self.cache.remove(&oldest);
+                self.stats.evictions += 1;
+            }
+        }
+    }
+}
+
+/// Statistics of the memos a solver used, collected for whoever asked for them. Memos
+/// filled on other threads are recorded once they are handed back.
+#[derive(Debug, Default, PartialEq)]
+pub struct MemoReport {
+    pub memos: Vec<(&'static str, MemoStats)>,
+}
+
+impl MemoReport {
+    pub fn record<K, V>(&mut self, memo: &Memo<K, V>) {
+        self.memos.push((memo.name, memo.stats));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::memo::{Memo, MemoReport, MemoStats};
+
+    fn fibonacci(memo: &mut Memo<u64, u64>, n: u64) -> u64 {
+        memo.call(n, |memo, &n| match n {
+            0 | 1 => n,
+            _ => fibonacci(memo, n - 1) + fibonacci(memo, n - 2),
+        })
+    }
+
+    #[test]
+    fn test_recursion_through_memo() {
+        let mut memo = Memo::new("fibonacci");
+
+        assert_eq!(fibonacci(&mut memo, 90), 2880067194370816120);
+        assert_eq!(
+            memo.stats(),
+            MemoStats {
+                hits: 88,
+                misses: 91,
+                evictions: 0
+            }
+        );
+        assert_eq!(memo.len(), 91);
+
+        let mut report = MemoReport::default();
+        report.record(&memo);
+        assert_eq!(report.memos, vec![("fibonacci", memo.stats())]);
+    }
+
+    #[test]
+    fn test_bound_evicts_oldest_entries() {
+        let mut memo = Memo::with_bound("squares", 2);
+
+        for n in [1, 2, 3, 1] {
+            memo.call(n, |_, n| n * n);
+        }
+
+        assert_eq!(memo.len(), 2);
+        assert_eq!(
+            memo.stats(),
+            MemoStats {
+                hits: 0,
+                misses: 4,
+                evictions: 2
+            }
+        );
+        assert_eq!(memo.call(3, |_, _| unreachable!()), 9);
+    }
+}
@@ -0,0 +1,267 @@
+//! Small parsing primitives that keep track of where in the input they are, so that
+//! errors can point at the offending line and column.
+use crate::utils::grid::Grid;
+use std::fmt::Display;
+use std::str::FromStr;
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq)]
+#[error("line {line}, column {column}: {message}")]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+/// Slice of the input along with its 1-based line and column.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Span<'a> {
+    text: &'a str,
+    line: usize,
+    column: usize,
+}
+
+impl<'a> Span<'a> {
+    pub fn new(text: &'a str) -> Span<'a> {
+        Span {
+            text,
+            line: 1,
+            column: 1,
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn text(self) -> &'a str {
+        self.text
+    }
+
+    pub fn line(self) -> usize {
+        self.line
+    }
+
+    pub fn error(self, message: impl Into<String>) -> ParseError {
+        ParseError {
+            line: self.line,
+            column: self.column,
+            message: message.into(),
+        }
+    }
+
+    /// Span of `part`, which has to be a subslice of this span's text.
+    fn sub_span(self, part: &'a str) -> Span<'a> {
+        let offset = part.as_ptr() as usize - self.text.as_ptr() as usize;
+        let prefix = &self.text[..offset];
+
+        match prefix.rfind('\n') {
+            Some(last_newline) => Span {
+                text: part,
+                line: self.line + prefix.matches('\n').count(),
+                column: offset - last_newline,
+            },
+            None => Span {
+                text: part,
+                line: self.line,
+                column: self.column + offset,
+            },
+        }
+    }
+
+    pub fn trim(self) -> Span<'a> {
+        self.sub_span(self.text.trim())
+    }
+
+    /// Counts the lines along the way, as looking each of them up with `sub_span`
+    /// would go through the whole input before it for every line.
+    pub fn lines(self) -> impl Iterator<Item = Span<'a>> {
+        self.text
+            .lines()
+            .enumerate()
+            .map(move |(index, line)| Span {
+                text: line,
+                line: self.line + index,
+                column: if index == 0 { self.column } else { 1 },
+            })
+    }
+
+    pub fn split(self, separator: &'a str) -> impl Iterator<Item = Span<'a>> {
+        self.text
+            .split(separator)
+            .map(move |part| self.sub_span(part))
+    }
+
+    /// Parts separated by any amount of whitespace.
+    pub fn words(self) -> impl Iterator<Item = Span<'a>> {
+        self.text
+            .split_whitespace()
+            .map(move |word| self.sub_span(word))
+    }
+
+    /// Parts separated by blank lines.
+    #[allow(dead_code)]
+    pub fn sections(self) -> impl Iterator<Item = Span<'a>> {
+        self.split("\n\n")
+    }
+
+    pub fn split_once(self, separator: &str) -> Result<(Span<'a>, Span<'a>), ParseError> {
+        match self.text.split_once(separator) {
+            Some((left, right)) => Ok((self.sub_span(left), self.sub_span(right))),
+            None => Err(self.error(format!("expected '{}' in '{}'", separator, self.text))),
+        }
+    }
+
+    pub fn parse<T>(self) -> Result<T, ParseError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        self.text
+            .parse()
+            .map_err(|e| self.error(format!("cannot parse '{}': {}", self.text, e)))
+    }
+}
+
+/// Every non-blank line parsed with `line`.
+pub fn lines<'a, T>(
+    span: Span<'a>,
+    line: impl Fn(Span<'a>) -> Result<T, ParseError>,
+) -> Result<Vec<T>, ParseError> {
+    span.lines()
+        .filter(|span| !span.text.trim().is_empty())
+        .map(line)
+        .collect()
+}
+
+/// Items between separators, each parsed with `item`.
+pub fn separated<'a, T>(
+    span: Span<'a>,
+    separator: &'a str,
+    item: impl Fn(Span<'a>) -> Result<T, ParseError>,
+) -> Result<Vec<T>, ParseError> {
+    span.split(separator)
+        .map(|part| item(part.trim()))
+        .collect()
+}
+
+/// Whitespace separated integers.
+pub fn integers<T>(span: Span) -> Result<Vec<T>, ParseError>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    span.words().map(Span::parse).collect()
+}
+
+/// Two parts around the first separator, parsed with `left` and `right`.
+pub fn pair<'a, A, B>(
+    span: Span<'a>,
+    separator: &str,
+    left: impl Fn(Span<'a>) -> Result<A, ParseError>,
+    right: impl Fn(Span<'a>) -> Result<B, ParseError>,
+) -> Result<(A, B), ParseError> {
+    let (left_span, right_span) = span.split_once(separator)?;
+
+    Ok((left(left_span.trim())?, right(right_span.trim())?))
+}
+
+/// Rectangular grid of characters mapped with `cell`, which returns `None` for
+/// characters that are not allowed.
+pub fn grid<T>(span: Span, cell: impl Fn(char) -> Option<T>) -> Result<Grid<T>, ParseError> {
+    let rows = lines(span, |line| {
+        line.text
+            .char_indices()
+            .map(|(offset, char)| {
+                cell(char).ok_or_else(|| {
+                    line.sub_span(&line.text[offset..])
+                        .error(format!("unexpected character '{}'", char))
+                })
+            })
+            .collect::<Result<Vec<T>, ParseError>>()
+    })?;
+
+    if let Some((index, row)) = rows
+        .iter()
+        .enumerate()
+        .find(|(_, row)| row.len() != rows[0].len())
+    {
+        let line = span
+            .lines()
+            .filter(|line| !line.text.trim().is_empty())
+            .nth(index);
+        return Err(line.unwrap_or(span).error(format!(
+            "expected a row of {} cells, found {}",
+            rows[0].len(),
+            row.len()
+        )));
+    }
+
+    Ok(Grid::make(rows))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::parse::{grid, integers, lines, pair, separated, ParseError, Span};
+
+    #[test]
+    fn test_positions_are_tracked() {
+        let span = Span::new("1 2\n\n3 x");
+        let sections = span.sections().collect::<Vec<_>>();
+
+        assert_eq!(integers::<i32>(sections[0]), Ok(vec![1, 2]));
+        assert_eq!(
+            integers::<i32>(sections[1]),
+            Err(ParseError {
+                line: 3,
+                column: 3,
+                message: "cannot parse 'x': invalid digit found in string".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_pairs_and_separated_lists() {
+        let parsed = lines(Span::new("1|2\n\n3|4\n"), |line| {
+            pair(line, "|", Span::parse::<u8>, Span::parse::<u8>)
+        });
+        assert_eq!(parsed, Ok(vec![(1, 2), (3, 4)]));
+
+        let missing_separator = lines(Span::new("1|2\n34"), |line| {
+            pair(line, "|", Span::parse::<u8>, Span::parse::<u8>)
+        });
+        assert_eq!(missing_separator.unwrap_err().line, 2);
+
+        let error = lines(Span::new("1|2\n\n3|x"), |line| {
+            pair(line, "|", Span::parse::<u8>, Span::parse::<u8>)
+        })
+        .unwrap_err();
+        assert_eq!((error.line, error.column), (3, 3));
+
+        assert_eq!(
+            separated(Span::new("5, 6,7"), ",", Span::parse::<u8>),
+            Ok(vec![5, 6, 7])
+        );
+    }
+
+    #[test]
+    fn test_grid() {
+        let parsed = grid(Span::new("#.\n.#"), |c| match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        })
+        .unwrap();
+        assert_eq!(parsed.grid, vec![vec![true, false], vec![false, true]]);
+
+        let error = grid(Span::new("..\n.?"), |c| (c == '.').then_some(c)).unwrap_err();
+        assert_eq!((error.line, error.column), (2, 2));
+
+        for empty in ["", " \n\n"] {
+            let parsed = grid(Span::new(empty), Some).unwrap();
+            assert!(parsed.grid.is_empty());
+            assert_eq!((parsed.width, parsed.height), (0, 0));
+        }
+
+        let error = grid(Span::new("..\n."), Some).unwrap_err();
+        assert_eq!(error.message, "expected a row of 2 cells, found 1");
+        assert_eq!(error.line, 2);
+    }
+}
@@ -1,25 +1,152 @@
-use crate::utils::direction::Direction4Way;
-use std::ops::{Add, Div, Mul, Neg, Sub};
+use crate::utils::direction::{Direction4Way, Direction8Way};
+use crate::utils::math::gcd;
+use std::fmt::{Debug, Display, Formatter};
+use std::hash::Hash;
+use std::num::ParseIntError;
+use std::ops::{Add, AddAssign, Div, Mul, Neg, Rem, Sub, SubAssign};
+use std::str::FromStr;
+use thiserror::Error;
 
-#[derive(Debug, Eq, PartialEq, Hash, Clone)]
-pub struct Point {
-    pub x: i64,
-    pub y: i64,
+#[derive(Debug, Error, PartialEq)]
+pub enum ParsePointError {
+    #[error("Point '{0}' is not a comma separated list of coordinates")]
+    InvalidFormat(String),
+    #[error("Cannot parse coordinate of point: {0}")]
+    InvalidCoordinate(#[from] ParseIntError),
 }
 
-impl Add for Point {
-    type Output = Self;
+/// Signed integer type usable as the component of a [`Point`] or `Point3`.
+pub trait Coordinate:
+    Copy
+    + Debug
+    + Display
+    + FromStr<Err = ParseIntError>
+    + Eq
+    + Ord
+    + Hash
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Rem<Output = Self>
+    + Neg<Output = Self>
+    + AddAssign
+    + SubAssign
+    + TryFrom<usize>
+{
+    const ZERO: Self;
+    const ONE: Self;
 
-    fn add(self, rhs: Self) -> Self::Output {
+    fn abs(self) -> Self;
+
+    fn checked_neg(self) -> Option<Self>;
+}
+
+macro_rules! impl_coordinate {
+    ($($t:ty),*) => {
+        $(
+            impl Coordinate for $t {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+
+                fn abs(self) -> Self {
+                    <$t>::abs(self)
+                }
+
+                fn checked_neg(self) -> Option<Self> {
+                    <$t>::checked_neg(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_coordinate!(i8, i16, i32, i64, i128, isize);
+
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Copy, PartialOrd, Ord)]
+pub struct Point<T = i64> {
+    pub x: T,
+    pub y: T,
+}
+
+impl<T: Coordinate> Point<T> {
+    pub fn from_index(x: usize, y: usize) -> Point<T> {
         Point {
-            x: self.x + rhs.x,
-            y: self.y + rhs.y,
+            x: T::try_from(x).ok().unwrap(),
+            y: T::try_from(y).ok().unwrap(),
+        }
+    }
+
+    /// Converts into a point over another integer type, or `None` when a component
+    /// does not fit.
+    pub fn cast<U: Coordinate + TryFrom<T>>(self) -> Option<Point<U>> {
+        Some(Point {
+            x: U::try_from(self.x).ok()?,
+            y: U::try_from(self.y).ok()?,
+        })
+    }
+
+    /// Unit step in screen space, where rows grow downwards as they do in a `Grid`,
+    /// so `Up` decreases `y`.
+    pub fn screen_step(direction: &Direction4Way) -> Point<T> {
+        match direction {
+            Direction4Way::Up => Point { x: T::ZERO, y: -T::ONE },
+            Direction4Way::Down => Point { x: T::ZERO, y: T::ONE },
+            Direction4Way::Left => Point { x: -T::ONE, y: T::ZERO },
+            Direction4Way::Right => Point { x: T::ONE, y: T::ZERO },
+        }
+    }
+
+    /// Unit step in math space, where `y` grows upwards as on a plotted graph,
+    /// so `Up` increases `y`.
+    pub fn math_step(direction: &Direction4Way) -> Point<T> {
+        match direction {
+            Direction4Way::Up => Point { x: T::ZERO, y: T::ONE },
+            Direction4Way::Down => Point { x: T::ZERO, y: -T::ONE },
+            Direction4Way::Left => Point { x: -T::ONE, y: T::ZERO },
+            Direction4Way::Right => Point { x: T::ONE, y: T::ZERO },
+        }
+    }
+
+    pub fn manhattan_distance(&self, other: &Point<T>) -> T {
+        (self.x - other.x).abs() + (self.y - other.y).abs()
+    }
+
+    pub fn chebyshev_distance(&self, other: &Point<T>) -> T {
+        (self.x - other.x).abs().max((self.y - other.y).abs())
+    }
+
+    /// Rotates 90 degrees counterclockwise about the origin in screen space, so
+    /// `Right` becomes `Up` just like `Direction4Way` turning left.
+    pub fn rotate_left(&self) -> Point<T> {
+        Point {
+            x: self.y,
+            y: -self.x,
+        }
+    }
+
+    /// Rotates 90 degrees clockwise about the origin in screen space, matching
+    /// `Direction4Way::turn_90_degrees_to_right`.
+    pub fn rotate_right(&self) -> Point<T> {
+        Point {
+            x: -self.y,
+            y: self.x,
+        }
+    }
+
+    /// Smallest step with the same direction, i.e. the vector divided by the gcd of
+    /// its components. Stepping a ray with it visits every lattice point on the line.
+    /// Points whose gcd does not fit into `T`, like `(T::MIN, 0)`, are kept as they are.
+    pub fn reduced(&self) -> Point<T> {
+        match gcd(self.x, self.y) {
+            Some(divisor) if divisor != T::ZERO => *self / divisor,
+            _ => *self,
         }
     }
 }
 
-impl Add for &Point {
-    type Output = Point;
+impl<T: Coordinate> Add for Point<T> {
+    type Output = Self;
 
     fn add(self, rhs: Self) -> Self::Output {
         Point {
@@ -29,7 +156,22 @@ impl Add for &Point {
     }
 }
 
-impl Sub for Point {
+impl<T: Coordinate> Add for &Point<T> {
+    type Output = Point<T>;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        *self + *rhs
+    }
+}
+
+impl<T: Coordinate> AddAssign for Point<T> {
+    fn add_assign(&mut self, rhs: Self) {
+        self.x += rhs.x;
+        self.y += rhs.y;
+    }
+}
+
+impl<T: Coordinate> Sub for Point<T> {
     type Output = Self;
 
     fn sub(self, rhs: Self) -> Self::Output {
@@ -40,7 +182,22 @@ impl Sub for Point {
     }
 }
 
-impl Neg for Point {
+impl<T: Coordinate> Sub for &Point<T> {
+    type Output = Point<T>;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        *self - *rhs
+    }
+}
+
+impl<T: Coordinate> SubAssign for Point<T> {
+    fn sub_assign(&mut self, rhs: Self) {
+        self.x -= rhs.x;
+        self.y -= rhs.y;
+    }
+}
+
+impl<T: Coordinate> Neg for Point<T> {
     type Output = Self;
 
     fn neg(self) -> Self::Output {
@@ -51,13 +208,276 @@ impl Neg for Point {
     }
 }
 
-impl From<&Direction4Way> for Point {
+impl<T: Coordinate> Neg for &Point<T> {
+    type Output = Point<T>;
+
+    fn neg(self) -> Self::Output {
+        -*self
+    }
+}
+
+impl<T: Coordinate> Mul<T> for Point<T> {
+    type Output = Self;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        Point {
+            x: self.x * rhs,
+            y: self.y * rhs,
+        }
+    }
+}
+
+impl<T: Coordinate> Mul<T> for &Point<T> {
+    type Output = Point<T>;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        *self * rhs
+    }
+}
+
+impl<T: Coordinate> Mul for Point<T> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Point {
+            x: self.x * rhs.x,
+            y: self.y * rhs.y,
+        }
+    }
+}
+
+impl<T: Coordinate> Div<T> for Point<T> {
+    type Output = Self;
+
+    fn div(self, rhs: T) -> Self::Output {
+        Point {
+            x: self.x / rhs,
+            y: self.y / rhs,
+        }
+    }
+}
+
+impl<T: Coordinate> Div for Point<T> {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        Point {
+            x: self.x / rhs.x,
+            y: self.y / rhs.y,
+        }
+    }
+}
+
+impl<T: Coordinate> Display for Point<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{},{}", self.x, self.y)
+    }
+}
+
+impl<T: Coordinate> FromStr for Point<T> {
+    type Err = ParsePointError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (x, y) = s
+            .split_once(',')
+            .ok_or_else(|| ParsePointError::InvalidFormat(s.to_string()))?;
+
+        Ok(Point {
+            x: x.trim().parse()?,
+            y: y.trim().parse()?,
+        })
+    }
+}
+
+/// Converts using the screen space convention of [`Point::screen_step`], as every
+/// direction in the puzzles moves across a `Grid`.
+impl<T: Coordinate> From<&Direction4Way> for Point<T> {
     fn from(value: &Direction4Way) -> Self {
-        match value {
-            Direction4Way::Up => Point { x: 0, y: 1 },
-            Direction4Way::Down => Point { x: 0, y: -1 },
-            Direction4Way::Left => Point { x: -1, y: 0 },
-            Direction4Way::Right => Point { x: 1, y: 0 },
+        Point::screen_step(value)
+    }
+}
+
+/// Screen space as well, diagonals moving one step along both axes.
+impl<T: Coordinate> From<&Direction8Way> for Point<T> {
+    fn from(value: &Direction8Way) -> Self {
+        let (x, y) = match value {
+            Direction8Way::Up => (T::ZERO, -T::ONE),
+            Direction8Way::UpRight => (T::ONE, -T::ONE),
+            Direction8Way::Right => (T::ONE, T::ZERO),
+            Direction8Way::DownRight => (T::ONE, T::ONE),
+            Direction8Way::Down => (T::ZERO, T::ONE),
+            Direction8Way::DownLeft => (-T::ONE, T::ONE),
+            Direction8Way::Left => (-T::ONE, T::ZERO),
+            Direction8Way::UpLeft => (-T::ONE, -T::ONE),
+        };
+
+        Point { x, y }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::direction::{Direction4Way, Direction8Way};
+    use crate::utils::grid::Grid;
+    use crate::utils::point::{ParsePointError, Point};
+
+    #[test]
+    fn test_arithmetic() {
+        let a = Point { x: 3, y: -2 };
+        let b = Point { x: 1, y: 5 };
+        let (a_ref, b_ref) = (&a, &b);
+
+        assert_eq!(a_ref + b_ref, Point { x: 4, y: 3 });
+        assert_eq!(a_ref - b_ref, Point { x: 2, y: -7 });
+        assert_eq!(-a_ref, Point { x: -3, y: 2 });
+        assert_eq!(a_ref * 3, Point { x: 9, y: -6 });
+        assert_eq!(a + b, Point { x: 4, y: 3 });
+        assert_eq!(a * b, Point { x: 3, y: -10 });
+        assert_eq!(Point { x: 9, y: -6 } / 3, Point { x: 3, y: -2 });
+        assert_eq!(
+            Point { x: 9, y: -6 } / Point { x: 3, y: 2 },
+            Point { x: 3, y: -3 }
+        );
+
+        let mut c = a;
+        c += b;
+        assert_eq!(c, Point { x: 4, y: 3 });
+        c -= b;
+        assert_eq!(c, a);
+    }
+
+    #[test]
+    fn test_ordering_is_by_x_then_y() {
+        let mut points = vec![
+            Point { x: 1, y: 0 },
+            Point { x: 0, y: 2 },
+            Point { x: 0, y: 1 },
+        ];
+        points.sort();
+
+        assert_eq!(
+            points,
+            vec![
+                Point { x: 0, y: 1 },
+                Point { x: 0, y: 2 },
+                Point { x: 1, y: 0 }
+            ]
+        );
+    }
+
+    #[test]
+    fn test_distances() {
+        let a = Point { x: 1, y: -2 };
+        let b = Point { x: -3, y: 4 };
+
+        assert_eq!(a.manhattan_distance(&b), 10);
+        assert_eq!(a.chebyshev_distance(&b), 6);
+        assert_eq!(a.manhattan_distance(&a), 0);
+    }
+
+    #[test]
+    fn test_rotations_match_direction_turns() {
+        for direction in Direction4Way::all_directions_turning_right(Direction4Way::Up) {
+            let step: Point = Point::screen_step(&direction);
+            let turned = Point::screen_step(&direction.turn_90_degrees_to_right());
+
+            assert_eq!(step.rotate_right(), turned);
+            assert_eq!(turned.rotate_left(), step);
+        }
+    }
+
+    #[test]
+    fn test_reduced() {
+        assert_eq!(Point { x: 4, y: -6 }.reduced(), Point { x: 2, y: -3 });
+        assert_eq!(Point { x: 0, y: -5 }.reduced(), Point { x: 0, y: -1 });
+        assert_eq!(Point { x: 3, y: 5 }.reduced(), Point { x: 3, y: 5 });
+        assert_eq!(Point { x: 0, y: 0 }.reduced(), Point { x: 0, y: 0 });
+        assert_eq!(
+            Point { x: i64::MIN, y: 2 }.reduced(),
+            Point { x: i64::MIN / 2, y: 1 }
+        );
+    }
+
+    #[test]
+    fn test_display_and_parsing_round_trip() {
+        let point = Point { x: -12, y: 7 };
+
+        assert_eq!(point.to_string(), "-12,7");
+        assert_eq!("-12,7".parse::<Point>(), Ok(point));
+        assert_eq!(" 3 , 4 ".parse::<Point>(), Ok(Point { x: 3, y: 4 }));
+        assert_eq!(
+            "3;4".parse::<Point>(),
+            Err(ParsePointError::InvalidFormat("3;4".to_string()))
+        );
+        assert!(matches!(
+            "3,a".parse::<Point>(),
+            Err(ParsePointError::InvalidCoordinate(_))
+        ));
+    }
+
+    #[test]
+    fn test_from_index() {
+        assert_eq!(Point::<i64>::from_index(2, 7), Point { x: 2, y: 7 });
+        assert_eq!(Point::<isize>::from_index(2, 7), Point { x: 2, y: 7 });
+    }
+
+    #[test]
+    fn test_cast_between_integer_types() {
+        let point: Point<isize> = Point { x: -3, y: 200 };
+
+        assert_eq!(point.cast::<i64>(), Some(Point { x: -3, y: 200 }));
+        assert_eq!(point.cast::<i8>(), None);
+    }
+
+    #[test]
+    fn test_generic_operations() {
+        let a: Point<i32> = Point { x: 4, y: -6 };
+
+        assert_eq!(a.reduced(), Point { x: 2, y: -3 });
+        assert_eq!(a.rotate_right(), Point { x: 6, y: 4 });
+        assert_eq!(a.manhattan_distance(&Point { x: 0, y: 0 }), 10);
+        assert_eq!(Point::<i8>::screen_step(&Direction4Way::Up), Point { x: 0, y: -1 });
+    }
+
+    #[test]
+    fn test_screen_step_follows_grid_rows() {
+        let grid = Grid::make(vec![vec!['a'], vec!['b']]);
+        let bottom = Point { x: 0, y: 1 };
+
+        assert_eq!(
+            grid.get_point(&(bottom + Point::screen_step(&Direction4Way::Up))),
+            Some(&'a')
+        );
+        assert_eq!(Point::from(&Direction4Way::Up), Point { x: 0, y: -1 });
+        assert_eq!(Point::from(&Direction4Way::Down), Point { x: 0, y: 1 });
+        assert_eq!(Point::from(&Direction8Way::UpRight), Point { x: 1, y: -1 });
+    }
+
+    #[test]
+    fn test_math_step_points_y_upwards() {
+        assert_eq!(Point::math_step(&Direction4Way::Up), Point { x: 0, y: 1 });
+        assert_eq!(
+            Point::math_step(&Direction4Way::Down),
+            Point { x: 0, y: -1 }
+        );
+        assert_eq!(
+            Point::math_step(&Direction4Way::Left),
+            Point { x: -1, y: 0 }
+        );
+        assert_eq!(
+            Point::math_step(&Direction4Way::Right),
+            Point { x: 1, y: 0 }
+        );
+    }
+
+    #[test]
+    fn test_conventions_agree_horizontally_and_mirror_vertically() {
+        for direction in Direction4Way::all_directions_turning_right(Direction4Way::Up) {
+            let screen: Point = Point::screen_step(&direction);
+            let math: Point = Point::math_step(&direction);
+
+            assert_eq!(screen.x, math.x);
+            assert_eq!(screen.y, -math.y);
         }
     }
 }
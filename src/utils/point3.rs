@@ -0,0 +1,286 @@
+use crate::utils::math::gcd;
+use crate::utils::point::{Coordinate, ParsePointError};
+use std::fmt::{Display, Formatter};
+use std::ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign};
+use std::str::FromStr;
+
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Copy, PartialOrd, Ord)]
+pub struct Point3<T = i64> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T: Coordinate> Point3<T> {
+    /// Converts into a point over another integer type, or `None` when a component
+    /// does not fit.
+    pub fn cast<U: Coordinate + TryFrom<T>>(self) -> Option<Point3<U>> {
+        Some(Point3 {
+            x: U::try_from(self.x).ok()?,
+            y: U::try_from(self.y).ok()?,
+            z: U::try_from(self.z).ok()?,
+        })
+    }
+
+    pub fn manhattan_distance(&self, other: &Point3<T>) -> T {
+        (self.x - other.x).abs() + (self.y - other.y).abs() + (self.z - other.z).abs()
+    }
+
+    pub fn chebyshev_distance(&self, other: &Point3<T>) -> T {
+        (self.x - other.x)
+            .abs()
+            .max((self.y - other.y).abs())
+            .max((self.z - other.z).abs())
+    }
+
+    /// Smallest step with the same direction, i.e. the vector divided by the gcd of
+    /// its components. Points whose gcd does not fit into `T` are kept as they are.
+    pub fn reduced(&self) -> Point3<T> {
+        match gcd(self.x, self.y).and_then(|divisor| gcd(divisor, self.z)) {
+            Some(divisor) if divisor != T::ZERO => *self / divisor,
+            _ => *self,
+        }
+    }
+}
+
+impl<T: Coordinate> Add for Point3<T> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Point3 {
+            x: self.x + rhs.x,
+            y: self.y + rhs.y,
+            z: self.z + rhs.z,
+        }
+    }
+}
+
+impl<T: Coordinate> Add for &Point3<T> {
+    type Output = Point3<T>;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        *self + *rhs
+    }
+}
+
+impl<T: Coordinate> AddAssign for Point3<T> {
+    fn add_assign(&mut self, rhs: Self) {
+        self.x += rhs.x;
+        self.y += rhs.y;
+        self.z += rhs.z;
+    }
+}
+
+impl<T: Coordinate> Sub for Point3<T> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Point3 {
+            x: self.x - rhs.x,
+            y: self.y - rhs.y,
+            z: self.z - rhs.z,
+        }
+    }
+}
+
+impl<T: Coordinate> Sub for &Point3<T> {
+    type Output = Point3<T>;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        *self - *rhs
+    }
+}
+
+impl<T: Coordinate> SubAssign for Point3<T> {
+    fn sub_assign(&mut self, rhs: Self) {
+        self.x -= rhs.x;
+        self.y -= rhs.y;
+        self.z -= rhs.z;
+    }
+}
+
+impl<T: Coordinate> Neg for Point3<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Point3 {
+            x: -self.x,
+            y: -self.y,
+            z: -self.z,
+        }
+    }
+}
+
+impl<T: Coordinate> Neg for &Point3<T> {
+    type Output = Point3<T>;
+
+    fn neg(self) -> Self::Output {
+        -*self
+    }
+}
+
+impl<T: Coordinate> Mul<T> for Point3<T> {
+    type Output = Self;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        Point3 {
+            x: self.x * rhs,
+            y: self.y * rhs,
+            z: self.z * rhs,
+        }
+    }
+}
+
+impl<T: Coordinate> Mul<T> for &Point3<T> {
+    type Output = Point3<T>;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        *self * rhs
+    }
+}
+
+impl<T: Coordinate> Mul for Point3<T> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Point3 {
+            x: self.x * rhs.x,
+            y: self.y * rhs.y,
+            z: self.z * rhs.z,
+        }
+    }
+}
+
+impl<T: Coordinate> Div<T> for Point3<T> {
+    type Output = Self;
+
+    fn div(self, rhs: T) -> Self::Output {
+        Point3 {
+            x: self.x / rhs,
+            y: self.y / rhs,
+            z: self.z / rhs,
+        }
+    }
+}
+
+impl<T: Coordinate> Div for Point3<T> {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        Point3 {
+            x: self.x / rhs.x,
+            y: self.y / rhs.y,
+            z: self.z / rhs.z,
+        }
+    }
+}
+
+impl<T: Coordinate> Display for Point3<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{},{},{}", self.x, self.y, self.z)
+    }
+}
+
+impl<T: Coordinate> FromStr for Point3<T> {
+    type Err = ParsePointError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut components = s.split(',');
+
+        match (
+            components.next(),
+            components.next(),
+            components.next(),
+            components.next(),
+        ) {
+            (Some(x), Some(y), Some(z), None) => Ok(Point3 {
+                x: x.trim().parse()?,
+                y: y.trim().parse()?,
+                z: z.trim().parse()?,
+            }),
+            _ => Err(ParsePointError::InvalidFormat(s.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::point::ParsePointError;
+    use crate::utils::point3::Point3;
+
+    #[test]
+    fn test_arithmetic() {
+        let a: Point3 = Point3 { x: 3, y: -2, z: 1 };
+        let b = Point3 { x: 1, y: 5, z: -4 };
+        let (a_ref, b_ref) = (&a, &b);
+
+        assert_eq!(a_ref + b_ref, Point3 { x: 4, y: 3, z: -3 });
+        assert_eq!(a_ref - b_ref, Point3 { x: 2, y: -7, z: 5 });
+        assert_eq!(-a_ref, Point3 { x: -3, y: 2, z: -1 });
+        assert_eq!(a_ref * 2, Point3 { x: 6, y: -4, z: 2 });
+        assert_eq!(
+            a * b,
+            Point3 {
+                x: 3,
+                y: -10,
+                z: -4
+            }
+        );
+        assert_eq!(Point3 { x: 6, y: -4, z: 2 } / 2, a);
+
+        let mut c = a;
+        c += b;
+        c -= b;
+        assert_eq!(c, a);
+    }
+
+    #[test]
+    fn test_distances_and_reduction() {
+        let a: Point3 = Point3 { x: 1, y: -2, z: 3 };
+        let b = Point3 { x: -3, y: 4, z: 3 };
+
+        assert_eq!(a.manhattan_distance(&b), 10);
+        assert_eq!(a.chebyshev_distance(&b), 6);
+        assert_eq!(
+            Point3 { x: 4, y: -6, z: 8 }.reduced(),
+            Point3 { x: 2, y: -3, z: 4 }
+        );
+        let origin: Point3 = Point3 { x: 0, y: 0, z: 0 };
+        assert_eq!(origin.reduced(), origin);
+    }
+
+    #[test]
+    fn test_cast() {
+        let point: Point3<i64> = Point3 {
+            x: 1,
+            y: -2,
+            z: 300,
+        };
+
+        assert_eq!(
+            point.cast::<i16>(),
+            Some(Point3 {
+                x: 1,
+                y: -2,
+                z: 300
+            })
+        );
+        assert_eq!(point.cast::<i8>(), None);
+    }
+
+    #[test]
+    fn test_display_and_parsing_round_trip() {
+        let point: Point3 = Point3 { x: -1, y: 2, z: 3 };
+
+        assert_eq!(point.to_string(), "-1,2,3");
+        assert_eq!("-1,2,3".parse::<Point3>(), Ok(point));
+        assert_eq!(
+            "1,2".parse::<Point3>(),
+            Err(ParsePointError::InvalidFormat("1,2".to_string()))
+        );
+        assert_eq!(
+            "1,2,3,4".parse::<Point3>(),
+            Err(ParsePointError::InvalidFormat("1,2,3,4".to_string()))
+        );
+    }
+}
@@ -0,0 +1,289 @@
+use std::collections::BTreeMap;
+use std::iter::from_fn;
+use std::ops::Range;
+
+/// Set of integers stored as sorted, disjoint half-open ranges. Touching ranges are
+/// merged, so every range in the set is followed by a gap.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RangeSet {
+    ranges: BTreeMap<i64, i64>,
+}
+
+impl RangeSet {
+    pub fn new() -> RangeSet {
+        RangeSet::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Amount of integers in the set.
+    pub fn len(&self) -> i64 {
+        self.ranges.iter().map(|(start, end)| end - start).sum()
+    }
+
+    pub fn contains(&self, value: i64) -> bool {
+        self.ranges
+            .range(..=value)
+            .next_back()
+            .is_some_and(|(_, end)| value < *end)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Range<i64>> + '_ {
+        self.ranges.iter().map(|(start, end)| *start..*end)
+    }
+
+    pub fn insert(&mut self, range: Range<i64>) {
+        if range.is_empty() {
+            return;
+        }
+
+        let touching = self
+            .ranges
+            .range(..=range.end)
+            .rev()
+            .take_while(|(_, end)| **end >= range.start)
+            .map(|(start, end)| (*start, *end))
+            .collect::<Vec<_>>();
+
+        let (mut start, mut end) = (range.start, range.end);
+        for (touching_start, touching_end) in touching {
+            self.ranges.remove(&touching_start);
+            start = start.min(touching_start);
+            end = end.max(touching_end);
+        }
+
+        self.ranges.insert(start, end);
+    }
+
+    pub fn remove(&mut self, range: Range<i64>) {
+        if range.is_empty() {
+            return;
+        }
+
+        let overlapping = self
+            .ranges
+            .range(..range.end)
+            .rev()
+            .take_while(|(_, end)| **end > range.start)
+            .map(|(start, end)| (*start, *end))
+            .collect::<Vec<_>>();
+
+        for (start, end) in overlapping {
+            self.ranges.remove(&start);
+            if start < range.start {
+                self.ranges.insert(start, range.start);
+            }
+            if end > range.end {
+                self.ranges.insert(range.end, end);
+            }
+        }
+    }
+
+    pub fn union(&self, other: &RangeSet) -> RangeSet {
+        let mut union = self.clone();
+        other.iter().for_each(|range| union.insert(range));
+
+        union
+    }
+
+    pub fn intersect(&self, other: &RangeSet) -> RangeSet {
+        let mut intersection = RangeSet::new();
+        let mut own = self.iter().peekable();
+        let mut others = other.iter().peekable();
+
+        while let (Some(a), Some(b)) = (own.peek(), others.peek()) {
+            intersection.insert(a.start.max(b.start)..a.end.min(b.end));
+
+            if a.end < b.end {
+                own.next();
+            } else {
+                others.next();
+            }
+        }
+
+        intersection
+    }
+
+    /// Maximal ranges inside `within` that are not in the set, in ascending order.
+    pub fn gaps(&self, within: Range<i64>) -> impl Iterator<Item = Range<i64>> + '_ {
+        let mut cursor = within.start;
+        let mut ranges = self
+            .ranges
+            .range(..within.end)
+            .map(|(start, end)| *start..*end)
+            .skip_while(move |range| range.end <= within.start);
+
+        from_fn(move || {
+            while cursor < within.end {
+                match ranges.next() {
+                    Some(range) if range.start > cursor => {
+                        let gap = cursor..range.start;
+                        cursor = range.end;
+                        return Some(gap);
+                    }
+                    Some(range) => cursor = cursor.max(range.end),
+                    None => {
+                        let gap = cursor..within.end;
+                        cursor = within.end;
+                        return Some(gap);
+                    }
+                }
+            }
+
+            None
+        })
+    }
+
+    /// Lowest gap inside `within` that fits at least `length` integers.
+    pub fn first_gap(&self, within: Range<i64>, length: i64) -> Option<Range<i64>> {
+        self.gaps(within).find(|gap| gap.end - gap.start >= length)
+    }
+}
+
+impl FromIterator<Range<i64>> for RangeSet {
+    fn from_iter<I: IntoIterator<Item = Range<i64>>>(iter: I) -> Self {
+        let mut set = RangeSet::new();
+        iter.into_iter().for_each(|range| set.insert(range));
+
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::ranges::RangeSet;
+    use proptest::prelude::*;
+    use std::ops::Range;
+
+    const UNIVERSE: i64 = 64;
+
+    #[test]
+    fn test_insert_merges_touching_ranges() {
+        let set: RangeSet = [0..3, 5..8, 3..5, 10..12].into_iter().collect();
+
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![0..8, 10..12]);
+        assert_eq!(set.len(), 10);
+    }
+
+    #[test]
+    fn test_remove_splits_ranges() {
+        let mut set = RangeSet::new();
+        set.insert(0..10);
+        set.remove(3..5);
+        set.remove(9..20);
+
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![0..3, 5..9]);
+        assert!(set.contains(8));
+        assert!(!set.contains(4));
+    }
+
+    #[test]
+    fn test_gaps() {
+        let set: RangeSet = [2..4, 6..7].into_iter().collect();
+
+        assert_eq!(set.gaps(0..10).collect::<Vec<_>>(), vec![0..2, 4..6, 7..10]);
+        assert_eq!(set.gaps(3..7).collect::<Vec<_>>(), vec![4..6]);
+        assert_eq!(set.first_gap(0..10, 3), Some(7..10));
+        assert_eq!(set.first_gap(0..10, 4), None);
+        assert_eq!(RangeSet::new().gaps(0..3).collect::<Vec<_>>(), vec![0..3]);
+    }
+
+    #[derive(Debug, Clone)]
+    enum Operation {
+        Insert(Range<i64>),
+        Remove(Range<i64>),
+    }
+
+    fn small_range() -> impl Strategy<Value = Range<i64>> {
+        (0..UNIVERSE, 0..16i64).prop_map(|(start, length)| start..(start + length).min(UNIVERSE))
+    }
+
+    fn operation() -> impl Strategy<Value = Operation> {
+        prop_oneof![
+            small_range().prop_map(Operation::Insert),
+            small_range().prop_map(Operation::Remove),
+        ]
+    }
+
+    fn apply(operations: &[Operation]) -> (RangeSet, Vec<bool>) {
+        let mut set = RangeSet::new();
+        let mut bits = vec![false; UNIVERSE as usize];
+
+        for operation in operations {
+            match operation {
+                Operation::Insert(range) => {
+                    set.insert(range.clone());
+                    range.clone().for_each(|i| bits[i as usize] = true);
+                }
+                Operation::Remove(range) => {
+                    set.remove(range.clone());
+                    range.clone().for_each(|i| bits[i as usize] = false);
+                }
+            }
+        }
+
+        (set, bits)
+    }
+
+    fn to_bits(set: &RangeSet) -> Vec<bool> {
+        (0..UNIVERSE).map(|i| set.contains(i)).collect()
+    }
+
+    fn naive_gaps(bits: &[bool]) -> Vec<Range<i64>> {
+        let mut gaps: Vec<Range<i64>> = vec![];
+        for (i, bit) in bits.iter().enumerate() {
+            let i = i as i64;
+            match gaps.last_mut() {
+                Some(gap) if !bit && gap.end == i => gap.end = i + 1,
+                _ if !bit => gaps.push(i..i + 1),
+                _ => {}
+            }
+        }
+
+        gaps
+    }
+
+    proptest! {
+        #[test]
+        fn test_matches_bitset(operations in prop::collection::vec(operation(), 0..20)) {
+            let (set, bits) = apply(&operations);
+
+            prop_assert_eq!(to_bits(&set), bits.clone());
+            prop_assert_eq!(set.len(), bits.iter().filter(|bit| **bit).count() as i64);
+            prop_assert!(set.iter().all(|range| !range.is_empty()));
+            prop_assert!(set.iter().zip(set.iter().skip(1)).all(|(a, b)| a.end < b.start));
+        }
+
+        #[test]
+        fn test_gaps_match_bitset(
+            operations in prop::collection::vec(operation(), 0..20),
+            length in 1..10i64,
+        ) {
+            let (set, bits) = apply(&operations);
+            let gaps = naive_gaps(&bits);
+
+            prop_assert_eq!(set.gaps(0..UNIVERSE).collect::<Vec<_>>(), gaps.clone());
+            prop_assert_eq!(
+                set.first_gap(0..UNIVERSE, length),
+                gaps.into_iter().find(|gap| gap.end - gap.start >= length)
+            );
+        }
+
+        #[test]
+        fn test_set_operations_match_bitset(
+            a in prop::collection::vec(operation(), 0..20),
+            b in prop::collection::vec(operation(), 0..20),
+        ) {
+            let (set_a, bits_a) = apply(&a);
+            let (set_b, bits_b) = apply(&b);
+
+            let union = bits_a.iter().zip(&bits_b).map(|(a, b)| *a || *b).collect::<Vec<_>>();
+            let intersection = bits_a.iter().zip(&bits_b).map(|(a, b)| *a && *b).collect::<Vec<_>>();
+
+            prop_assert_eq!(to_bits(&set_a.union(&set_b)), union);
+            prop_assert_eq!(to_bits(&set_a.intersect(&set_b)), intersection);
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use crate::utils::point::Point;
+
+/// Axis aligned rectangle with inclusive corners.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rect {
+    pub min: Point,
+    pub max: Point,
+}
+
+impl Rect {
+    pub fn from_point(point: Point) -> Rect {
+        Rect {
+            min: point,
+            max: point,
+        }
+    }
+
+    pub fn width(&self) -> i64 {
+        self.max.x - self.min.x + 1
+    }
+
+    pub fn height(&self) -> i64 {
+        self.max.y - self.min.y + 1
+    }
+
+    pub fn contains(&self, point: &Point) -> bool {
+        (self.min.x..=self.max.x).contains(&point.x) && (self.min.y..=self.max.y).contains(&point.y)
+    }
+
+    pub fn extend(&mut self, point: &Point) {
+        self.min.x = self.min.x.min(point.x);
+        self.min.y = self.min.y.min(point.y);
+        self.max.x = self.max.x.max(point.x);
+        self.max.y = self.max.y.max(point.y);
+    }
+
+    /// Points row by row from the top-left corner.
+    pub fn points(&self) -> impl Iterator<Item = Point> + use<> {
+        let Rect { min, max } = *self;
+
+        (min.y..=max.y).flat_map(move |y| (min.x..=max.x).map(move |x| Point { x, y }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::point::Point;
+    use crate::utils::rect::Rect;
+
+    #[test]
+    fn test_extending_and_containing() {
+        let mut rect = Rect::from_point(Point { x: 1, y: 1 });
+        rect.extend(&Point { x: -1, y: 3 });
+
+        assert_eq!(
+            rect,
+            Rect {
+                min: Point { x: -1, y: 1 },
+                max: Point { x: 1, y: 3 }
+            }
+        );
+        assert_eq!((rect.width(), rect.height()), (3, 3));
+        assert!(rect.contains(&Point { x: 0, y: 2 }));
+        assert!(!rect.contains(&Point { x: 2, y: 2 }));
+    }
+
+    #[test]
+    fn test_points_are_row_major() {
+        let rect = Rect {
+            min: Point { x: 0, y: 0 },
+            max: Point { x: 1, y: 1 },
+        };
+
+        assert_eq!(
+            rect.points().collect::<Vec<_>>(),
+            vec![
+                Point { x: 0, y: 0 },
+                Point { x: 1, y: 0 },
+                Point { x: 0, y: 1 },
+                Point { x: 1, y: 1 }
+            ]
+        );
+    }
+}
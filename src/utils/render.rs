@@ -0,0 +1,237 @@
+use crate::utils::grid::GridView;
+use crate::utils::point::Point;
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Formatter};
+use std::io::Write;
+use std::marker::PhantomData;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum RenderError {
+    #[error("Writing the rendered grid failed: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Encoding the rendered grid as PNG failed: {0}")]
+    Png(#[from] png::EncodingError),
+}
+
+/// Character a cell is drawn with when a grid is rendered.
+pub trait CellGlyph {
+    fn glyph(&self) -> char;
+}
+
+impl CellGlyph for char {
+    fn glyph(&self) -> char {
+        *self
+    }
+}
+
+pub type Rgb = [u8; 3];
+
+/// Colours used for each glyph when a rendered grid is exported as an image.
+#[derive(Debug, Clone)]
+pub struct Palette {
+    colors: HashMap<char, Rgb>,
+    default: Rgb,
+}
+
+impl Palette {
+    pub fn new(default: Rgb) -> Palette {
+        Palette {
+            colors: HashMap::new(),
+            default,
+        }
+    }
+
+    pub fn with(mut self, glyph: char, color: Rgb) -> Palette {
+        self.colors.insert(glyph, color);
+        self
+    }
+
+    pub fn color(&self, glyph: char) -> Rgb {
+        *self.colors.get(&glyph).unwrap_or(&self.default)
+    }
+}
+
+/// Draws the bounding box of a grid with optional overlays. Overlays added later are
+/// drawn on top of earlier ones, and points outside of the grid are ignored.
+pub struct GridRenderer<'a, T, G: GridView<T>> {
+    grid: &'a G,
+    empty: char,
+    overlays: Vec<(HashSet<Point>, char)>,
+    cell: PhantomData<&'a T>,
+}
+
+impl<'a, T: CellGlyph, G: GridView<T>> GridRenderer<'a, T, G> {
+    pub fn new(grid: &'a G) -> GridRenderer<'a, T, G> {
+        GridRenderer {
+            grid,
+            empty: '.',
+            overlays: vec![],
+            cell: PhantomData,
+        }
+    }
+
+    /// Glyph for positions inside the bounds that have no cell, as in a `SparseGrid`.
+    pub fn empty(mut self, glyph: char) -> Self {
+        self.empty = glyph;
+        self
+    }
+
+    pub fn overlay(mut self, points: impl IntoIterator<Item = Point>, glyph: char) -> Self {
+        self.overlays.push((points.into_iter().collect(), glyph));
+        self
+    }
+
+    pub fn rows(&self) -> Vec<Vec<char>> {
+        let Some(bounds) = self.grid.bounds() else {
+            return vec![];
+        };
+
+        (bounds.min.y..=bounds.max.y)
+            .map(|y| {
+                (bounds.min.x..=bounds.max.x)
+                    .map(|x| self.glyph_at(&Point { x, y }))
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn glyph_at(&self, point: &Point) -> char {
+        self.overlays
+            .iter()
+            .rev()
+            .find(|(points, _)| points.contains(point))
+            .map(|(_, glyph)| *glyph)
+            .or_else(|| self.grid.get_point(point).map(CellGlyph::glyph))
+            .unwrap_or(self.empty)
+    }
+
+    /// RGB pixel rows where every cell is a `scale` by `scale` square.
+    fn pixels(&self, palette: &Palette, scale: usize) -> (usize, usize, Vec<u8>) {
+        let rows = self.rows();
+        let height = rows.len() * scale;
+        let width = rows.first().map_or(0, |row| row.len()) * scale;
+
+        let data = rows
+            .iter()
+            .flat_map(|row| {
+                let line = row
+                    .iter()
+                    .flat_map(|glyph| palette.color(*glyph).repeat(scale))
+                    .collect::<Vec<u8>>();
+
+                std::iter::repeat_n(line, scale).flatten()
+            })
+            .collect();
+
+        (width, height, data)
+    }
+
+    /// Writes a binary PPM (P6) image.
+    pub fn write_ppm(
+        &self,
+        mut writer: impl Write,
+        palette: &Palette,
+        scale: usize,
+    ) -> Result<(), RenderError> {
+        let (width, height, data) = self.pixels(palette, scale);
+
+        write!(writer, "P6\n{} {}\n255\n", width, height)?;
+        writer.write_all(&data)?;
+
+        Ok(())
+    }
+
+    pub fn write_png(
+        &self,
+        writer: impl Write,
+        palette: &Palette,
+        scale: usize,
+    ) -> Result<(), RenderError> {
+        let (width, height, data) = self.pixels(palette, scale);
+
+        let mut encoder = png::Encoder::new(writer, width as u32, height as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut png_writer = encoder.write_header()?;
+        png_writer.write_image_data(&data)?;
+
+        Ok(())
+    }
+}
+
+impl<T: CellGlyph, G: GridView<T>> Display for GridRenderer<'_, T, G> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let lines = self
+            .rows()
+            .into_iter()
+            .map(|row| row.into_iter().collect::<String>())
+            .collect::<Vec<_>>();
+
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::grid::Grid;
+    use crate::utils::point::Point;
+    use crate::utils::render::{GridRenderer, Palette};
+    use crate::utils::sparse_grid::SparseGrid;
+
+    fn get_grid() -> Grid<char> {
+        Grid::make(vec![vec!['.', '#', '.'], vec!['.', '.', '.']])
+    }
+
+    #[test]
+    fn test_rendering_text_with_overlays() {
+        let grid = get_grid();
+        let rendered = GridRenderer::new(&grid)
+            .overlay([Point { x: 0, y: 0 }, Point { x: 0, y: 1 }], 'X')
+            .overlay([Point { x: 0, y: 1 }, Point { x: 5, y: 5 }], 'O')
+            .to_string();
+
+        assert_eq!(rendered, "X#.\nO..");
+    }
+
+    #[test]
+    fn test_rendering_sparse_grid_fills_empty_cells() {
+        let grid: SparseGrid<char> = [(Point { x: -1, y: 0 }, 'a'), (Point { x: 1, y: 1 }, 'b')]
+            .into_iter()
+            .collect();
+
+        assert_eq!(GridRenderer::new(&grid).empty(' ').to_string(), "a  \n  b");
+    }
+
+    #[test]
+    fn test_writing_ppm() {
+        let grid = Grid::make(vec![vec!['.', '#']]);
+        let palette = Palette::new([0, 0, 0]).with('#', [255, 0, 0]);
+        let mut output = vec![];
+
+        GridRenderer::new(&grid)
+            .write_ppm(&mut output, &palette, 2)
+            .unwrap();
+
+        let header = b"P6\n4 2\n255\n";
+        assert_eq!(&output[..header.len()], header);
+        assert_eq!(
+            &output[header.len()..],
+            [[0, 0, 0], [0, 0, 0], [255, 0, 0], [255, 0, 0]]
+                .repeat(2)
+                .concat()
+        );
+    }
+
+    #[test]
+    fn test_writing_png() {
+        let grid = get_grid();
+        let mut output = vec![];
+
+        GridRenderer::new(&grid)
+            .write_png(&mut output, &Palette::new([255, 255, 255]), 3)
+            .unwrap();
+
+        assert_eq!(&output[..8], b"\x89PNG\r\n\x1a\n");
+    }
+}
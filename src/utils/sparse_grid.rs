@@ -0,0 +1,227 @@
+use crate::utils::grid::{Grid, GridView};
+use crate::utils::point::Point;
+use crate::utils::rect::Rect;
+use std::collections::HashMap;
+
+/// Grid on an unbounded plane that only stores the occupied cells.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SparseGrid<T> {
+    cells: HashMap<Point, T>,
+    bounds: Option<Rect>,
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        SparseGrid {
+            cells: HashMap::new(),
+            bounds: None,
+        }
+    }
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> SparseGrid<T> {
+        SparseGrid::default()
+    }
+
+    /// Keeps only the cells of a dense grid accepted by the filter, so e.g. the
+    /// empty tiles of a map are not stored.
+    pub fn from_grid_filtered(grid: Grid<T>, keep: impl Fn(&T) -> bool) -> SparseGrid<T> {
+        grid.grid
+            .into_iter()
+            .enumerate()
+            .flat_map(|(y, row)| {
+                row.into_iter()
+                    .enumerate()
+                    .map(move |(x, value)| (Point::from_index(x, y), value))
+            })
+            .filter(|(_, value)| keep(value))
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn get_point_mut(&mut self, point: &Point) -> Option<&mut T> {
+        self.cells.get_mut(point)
+    }
+
+    pub fn insert(&mut self, point: Point, value: T) -> Option<T> {
+        match &mut self.bounds {
+            Some(bounds) => bounds.extend(&point),
+            None => self.bounds = Some(Rect::from_point(point)),
+        }
+
+        self.cells.insert(point, value)
+    }
+
+    pub fn remove(&mut self, point: &Point) -> Option<T> {
+        let removed = self.cells.remove(point)?;
+
+        if let Some(bounds) = self.bounds {
+            let is_on_edge = point.x == bounds.min.x
+                || point.x == bounds.max.x
+                || point.y == bounds.min.y
+                || point.y == bounds.max.y;
+
+            if is_on_edge {
+                self.bounds = Self::calculate_bounds(self.cells.keys());
+            }
+        }
+
+        Some(removed)
+    }
+
+    fn calculate_bounds<'a>(points: impl Iterator<Item = &'a Point>) -> Option<Rect> {
+        points.fold(None, |bounds, point| match bounds {
+            None => Some(Rect::from_point(*point)),
+            Some(mut bounds) => {
+                bounds.extend(point);
+                Some(bounds)
+            }
+        })
+    }
+}
+
+impl<T: Clone> SparseGrid<T> {
+    /// Dense copy of the bounding box with missing cells set to `empty`. The top-left
+    /// corner of the bounding box becomes the origin of the returned grid.
+    pub fn to_grid(&self, empty: T) -> Option<Grid<T>> {
+        let bounds = self.bounds?;
+
+        Some(Grid::make(
+            (bounds.min.y..=bounds.max.y)
+                .map(|y| {
+                    (bounds.min.x..=bounds.max.x)
+                        .map(|x| {
+                            self.cells
+                                .get(&Point { x, y })
+                                .cloned()
+                                .unwrap_or_else(|| empty.clone())
+                        })
+                        .collect()
+                })
+                .collect(),
+        ))
+    }
+}
+
+impl<T> GridView<T> for SparseGrid<T> {
+    fn get_point(&self, point: &Point) -> Option<&T> {
+        self.cells.get(point)
+    }
+
+    fn points<'a>(&'a self) -> impl Iterator<Item = (Point, &'a T)>
+    where
+        T: 'a,
+    {
+        self.cells.iter().map(|(point, value)| (*point, value))
+    }
+
+    fn bounds(&self) -> Option<Rect> {
+        self.bounds
+    }
+}
+
+impl<T> FromIterator<(Point, T)> for SparseGrid<T> {
+    fn from_iter<I: IntoIterator<Item = (Point, T)>>(iter: I) -> Self {
+        let mut grid = SparseGrid::new();
+        for (point, value) in iter {
+            grid.insert(point, value);
+        }
+
+        grid
+    }
+}
+
+impl<T> From<Grid<T>> for SparseGrid<T> {
+    fn from(grid: Grid<T>) -> Self {
+        SparseGrid::from_grid_filtered(grid, |_| true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::grid::{Grid, GridView};
+    use crate::utils::point::Point;
+    use crate::utils::rect::Rect;
+    use crate::utils::sparse_grid::SparseGrid;
+
+    #[test]
+    fn test_bounds_follow_inserts_and_removes() {
+        let mut grid = SparseGrid::new();
+        assert_eq!(grid.bounds(), None);
+
+        grid.insert(Point { x: -5, y: 2 }, 'a');
+        grid.insert(Point { x: 3, y: -1 }, 'b');
+        grid.insert(Point { x: 0, y: 0 }, 'c');
+        assert_eq!(
+            grid.bounds(),
+            Some(Rect {
+                min: Point { x: -5, y: -1 },
+                max: Point { x: 3, y: 2 }
+            })
+        );
+
+        assert_eq!(grid.remove(&Point { x: -5, y: 2 }), Some('a'));
+        assert_eq!(
+            grid.bounds(),
+            Some(Rect {
+                min: Point { x: 0, y: -1 },
+                max: Point { x: 3, y: 0 }
+            })
+        );
+
+        grid.remove(&Point { x: 3, y: -1 });
+        grid.remove(&Point { x: 0, y: 0 });
+        assert_eq!(grid.bounds(), None);
+        assert!(grid.is_empty());
+    }
+
+    #[test]
+    fn test_neighbours_skip_missing_cells() {
+        let grid: SparseGrid<char> = [(Point { x: 0, y: -1 }, 'a'), (Point { x: 1, y: 1 }, 'b')]
+            .into_iter()
+            .collect();
+
+        assert_eq!(
+            grid.neighbours(&Point { x: 0, y: 0 }).collect::<Vec<_>>(),
+            vec![(Point { x: 0, y: -1 }, &'a')]
+        );
+        assert_eq!(
+            grid.neighbours_with_diagonals(&Point { x: 0, y: 0 })
+                .count(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_round_trip_with_dense_grid() {
+        let dense = Grid::make(vec![vec!['.', '#'], vec!['#', '.']]);
+        let sparse = SparseGrid::from_grid_filtered(dense.clone(), |cell| *cell == '#');
+
+        assert_eq!(sparse.len(), 2);
+        assert_eq!(sparse.get_point(&Point { x: 1, y: 0 }), Some(&'#'));
+        assert_eq!(sparse.to_grid('.').unwrap().grid, dense.grid);
+        assert_eq!(SparseGrid::from(dense).len(), 4);
+    }
+
+    #[test]
+    fn test_to_grid_moves_bounds_to_origin() {
+        let sparse: SparseGrid<char> =
+            [(Point { x: -2, y: -2 }, 'a'), (Point { x: -1, y: -1 }, 'b')]
+                .into_iter()
+                .collect();
+
+        assert_eq!(
+            sparse.to_grid('.').unwrap().grid,
+            vec![vec!['a', '.'], vec!['.', 'b']]
+        );
+        assert!(SparseGrid::<char>::new().to_grid('.').is_none());
+    }
+}
@@ -0,0 +1,260 @@
+use crate::utils::grid::{Grid, GridView};
+use crate::utils::render::{CellGlyph, GridRenderer, Palette, RenderError};
+use std::fs;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum VisualizeError {
+    #[error("Writing a frame failed: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Rendering a frame failed: {0}")]
+    Render(#[from] RenderError),
+}
+
+/// Single step of a simulation as rows of glyphs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Frame {
+    pub title: String,
+    pub rows: Vec<Vec<char>>,
+}
+
+impl Frame {
+    pub fn new(title: impl Into<String>, rows: Vec<Vec<char>>) -> Frame {
+        Frame {
+            title: title.into(),
+            rows,
+        }
+    }
+
+    pub fn from_renderer<T: CellGlyph, G: GridView<T>>(
+        title: impl Into<String>,
+        renderer: &GridRenderer<T, G>,
+    ) -> Frame {
+        Frame::new(title, renderer.rows())
+    }
+
+    pub fn from_text(title: impl Into<String>, text: &str) -> Frame {
+        Frame::new(
+            title,
+            text.lines().map(|line| line.chars().collect()).collect(),
+        )
+    }
+
+    pub fn to_text(&self) -> String {
+        self.rows
+            .iter()
+            .map(|row| row.iter().collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Rows padded with spaces into a rectangle, as images need every row to be equally wide.
+    fn to_grid(&self) -> Grid<char> {
+        let width = self.rows.iter().map(Vec::len).max().unwrap_or(0).max(1);
+        let mut rows = self.rows.clone();
+        if rows.is_empty() {
+            rows.push(vec![]);
+        }
+
+        Grid::make(
+            rows.into_iter()
+                .map(|mut row| {
+                    row.resize(width, ' ');
+                    row
+                })
+                .collect(),
+        )
+    }
+}
+
+/// Opt-in hook solvers emit the intermediate states of a simulation to.
+pub trait Visualize {
+    fn frame(&mut self, frame: Frame) -> Result<(), VisualizeError>;
+}
+
+/// Keeps every frame in memory.
+#[cfg(test)]
+#[derive(Debug, Default)]
+pub struct FrameRecorder {
+    pub frames: Vec<Frame>,
+}
+
+#[cfg(test)]
+impl Visualize for FrameRecorder {
+    fn frame(&mut self, frame: Frame) -> Result<(), VisualizeError> {
+        self.frames.push(frame);
+        Ok(())
+    }
+}
+
+/// Plays frames in a terminal, colouring glyphs found in the palette.
+pub struct TerminalPlayer<W: Write> {
+    writer: W,
+    palette: Palette,
+    delay: Duration,
+}
+
+impl<W: Write> TerminalPlayer<W> {
+    pub fn new(writer: W, palette: Palette, delay: Duration) -> TerminalPlayer<W> {
+        TerminalPlayer {
+            writer,
+            palette,
+            delay,
+        }
+    }
+
+    fn colored_row(&self, row: &[char]) -> String {
+        row.iter()
+            .map(|glyph| match self.palette.color(*glyph) {
+                [r, g, b] => format!("\x1b[38;2;{r};{g};{b}m{glyph}"),
+            })
+            .collect::<String>()
+            + "\x1b[0m"
+    }
+}
+
+impl<W: Write> Visualize for TerminalPlayer<W> {
+    fn frame(&mut self, frame: Frame) -> Result<(), VisualizeError> {
+        let body = frame
+            .rows
+            .iter()
+            .map(|row| self.colored_row(row))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        write!(self.writer, "\x1b[2J\x1b[H{}\n{}\n", frame.title, body)?;
+        self.writer.flush()?;
+        thread::sleep(self.delay);
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum FrameFormat {
+    Text,
+    Ppm,
+    Png,
+}
+
+impl FrameFormat {
+    fn extension(&self) -> &'static str {
+        match self {
+            FrameFormat::Text => "txt",
+            FrameFormat::Ppm => "ppm",
+            FrameFormat::Png => "png",
+        }
+    }
+}
+
+/// Writes every frame into its own numbered file in a directory.
+pub struct FrameDump {
+    directory: PathBuf,
+    format: FrameFormat,
+    palette: Palette,
+    scale: usize,
+    next_index: usize,
+}
+
+impl FrameDump {
+    pub fn create(
+        directory: impl Into<PathBuf>,
+        format: FrameFormat,
+        palette: Palette,
+        scale: usize,
+    ) -> Result<FrameDump, VisualizeError> {
+        let directory = directory.into();
+        fs::create_dir_all(&directory)?;
+
+        Ok(FrameDump {
+            directory,
+            format,
+            palette,
+            scale,
+            next_index: 0,
+        })
+    }
+}
+
+impl Visualize for FrameDump {
+    fn frame(&mut self, frame: Frame) -> Result<(), VisualizeError> {
+        let path = self.directory.join(format!(
+            "frame_{:05}.{}",
+            self.next_index,
+            self.format.extension()
+        ));
+        self.next_index += 1;
+        let mut writer = BufWriter::new(File::create(path)?);
+
+        match self.format {
+            FrameFormat::Text => writeln!(writer, "{}\n{}", frame.title, frame.to_text())?,
+            FrameFormat::Ppm => GridRenderer::new(&frame.to_grid()).write_ppm(
+                &mut writer,
+                &self.palette,
+                self.scale,
+            )?,
+            FrameFormat::Png => GridRenderer::new(&frame.to_grid()).write_png(
+                &mut writer,
+                &self.palette,
+                self.scale,
+            )?,
+        }
+        writer.flush()?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::render::Palette;
+    use crate::utils::visualize::{Frame, FrameDump, FrameFormat, TerminalPlayer, Visualize};
+    use std::fs;
+    use std::time::Duration;
+
+    #[test]
+    fn test_terminal_player_colours_glyphs() {
+        let mut output = vec![];
+        let palette = Palette::new([1, 2, 3]);
+
+        TerminalPlayer::new(&mut output, palette, Duration::ZERO)
+            .frame(Frame::from_text("Step 1", "#"))
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "\x1b[2J\x1b[HStep 1\n\x1b[38;2;1;2;3m#\x1b[0m\n"
+        );
+    }
+
+    #[test]
+    fn test_frame_dump_writes_numbered_files() {
+        let directory = std::env::temp_dir().join(format!("aoc-frames-{}", std::process::id()));
+        let mut dump =
+            FrameDump::create(&directory, FrameFormat::Text, Palette::new([0, 0, 0]), 1).unwrap();
+
+        dump.frame(Frame::from_text("first", "ab\nc")).unwrap();
+        dump.frame(Frame::from_text("second", "")).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(directory.join("frame_00000.txt")).unwrap(),
+            "first\nab\nc\n"
+        );
+        assert!(directory.join("frame_00001.txt").exists());
+
+        let mut image_dump =
+            FrameDump::create(&directory, FrameFormat::Ppm, Palette::new([0, 0, 0]), 1).unwrap();
+        image_dump
+            .frame(Frame::from_text("ragged", "ab\nc"))
+            .unwrap();
+        let image = fs::read(directory.join("frame_00000.ppm")).unwrap();
+        assert!(image.starts_with(b"P6\n2 2\n255\n"));
+
+        fs::remove_dir_all(directory).unwrap();
+    }
+}
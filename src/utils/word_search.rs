@@ -0,0 +1,247 @@
+use crate::utils::direction::Direction8Way;
+use crate::utils::grid::GridView;
+use crate::utils::point::Point;
+
+/// A word found in a grid, read from `start` towards `direction`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WordMatch {
+    pub start: Point,
+    pub direction: Direction8Way,
+    pub cells: Vec<Point>,
+}
+
+/// Every occurrence of `word` in any of the eight directions, ordered by the start
+/// point and then clockwise from `Up`. Palindromes are found once in each direction
+/// they read in, while a single-letter word reads the same in every direction and is
+/// found once per cell, reading `Up`.
+pub fn find_word<T: PartialEq, G: GridView<T>>(grid: &G, word: &[T]) -> Vec<WordMatch> {
+    let Some(first) = word.first() else {
+        return vec![];
+    };
+    let directions = match word.len() {
+        1 => &Direction8Way::ALL[..1],
+        _ => &Direction8Way::ALL[..],
+    };
+
+    grid.points()
+        .filter(|(_, value)| *value == first)
+        .flat_map(|(start, _)| {
+            directions.iter().filter_map(move |direction| {
+                let step = Point::from(direction);
+                let cells = (0..word.len() as i64)
+                    .map(|index| start + step * index)
+                    .collect::<Vec<_>>();
+
+                cells
+                    .iter()
+                    .zip(word)
+                    .all(|(cell, expected)| grid.get_point(cell) == Some(expected))
+                    .then_some(WordMatch {
+                        start,
+                        direction: *direction,
+                        cells,
+                    })
+            })
+        })
+        .collect()
+}
+
+/// Occurrences of several words, tagged with the index of the word found.
+#[allow(dead_code)]
+pub fn find_words<T: PartialEq, G: GridView<T>>(
+    grid: &G,
+    words: &[&[T]],
+) -> Vec<(usize, WordMatch)> {
+    words
+        .iter()
+        .enumerate()
+        .flat_map(|(index, word)| {
+            find_word(grid, word)
+                .into_iter()
+                .map(move |word_match| (index, word_match))
+        })
+        .collect()
+}
+
+/// Shape of cells relative to its top-left corner. Cells left out of the pattern
+/// match anything.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pattern<T> {
+    cells: Vec<(Point, T)>,
+    width: i64,
+    height: i64,
+}
+
+impl<T: PartialEq + Clone> Pattern<T> {
+    /// Pattern just large enough to hold the given cells.
+    #[allow(dead_code)]
+    pub fn new(cells: Vec<(Point, T)>) -> Pattern<T> {
+        let width = cells
+            .iter()
+            .map(|(point, _)| point.x + 1)
+            .max()
+            .unwrap_or(0);
+        let height = cells
+            .iter()
+            .map(|(point, _)| point.y + 1)
+            .max()
+            .unwrap_or(0);
+
+        Pattern::with_size(cells, width, height)
+    }
+
+    fn with_size(mut cells: Vec<(Point, T)>, width: i64, height: i64) -> Pattern<T> {
+        cells.sort_by_key(|(point, _)| (point.y, point.x));
+
+        Pattern {
+            cells,
+            width,
+            height,
+        }
+    }
+
+    pub fn rotate_cw(&self) -> Pattern<T> {
+        let cells = self
+            .cells
+            .iter()
+            .map(|(point, value)| {
+                let rotated = Point {
+                    x: self.height - 1 - point.y,
+                    y: point.x,
+                };
+                (rotated, value.clone())
+            })
+            .collect();
+
+        Pattern::with_size(cells, self.height, self.width)
+    }
+
+    /// The four rotations of the pattern, leaving out the ones that look the same.
+    pub fn rotations(&self) -> Vec<Pattern<T>> {
+        let mut rotations: Vec<Pattern<T>> = vec![];
+        let mut current = self.clone();
+        for _ in 0..4 {
+            let next = current.rotate_cw();
+            if !rotations.contains(&current) {
+                rotations.push(current);
+            }
+            current = next;
+        }
+
+        rotations
+    }
+
+    /// Top-left corners where every cell of the pattern matches the grid.
+    pub fn find<G: GridView<T>>(&self, grid: &G) -> Vec<Point> {
+        grid.points()
+            .map(|(corner, _)| corner)
+            .filter(|corner| {
+                self.cells
+                    .iter()
+                    .all(|(point, value)| grid.get_point(&(*corner + *point)) == Some(value))
+            })
+            .collect()
+    }
+}
+
+impl Pattern<char> {
+    /// Pattern drawn as lines of text, where `wildcard` marks cells matching anything.
+    pub fn parse(text: &str, wildcard: char) -> Pattern<char> {
+        let cells = text
+            .lines()
+            .enumerate()
+            .flat_map(|(y, line)| {
+                line.chars()
+                    .enumerate()
+                    .filter(|(_, char)| *char != wildcard)
+                    .map(move |(x, char)| (Point::from_index(x, y), char))
+            })
+            .collect();
+        let width = text
+            .lines()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0);
+
+        Pattern::with_size(cells, width as i64, text.lines().count() as i64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::direction::Direction8Way;
+    use crate::utils::grid::Grid;
+    use crate::utils::point::Point;
+    use crate::utils::word_search::{find_word, find_words, Pattern};
+
+    fn get_grid() -> Grid<char> {
+        Grid::make(vec![
+            "CATT".chars().collect(),
+            "A..A".chars().collect(),
+            "T..C".chars().collect(),
+        ])
+    }
+
+    #[test]
+    fn test_find_word_in_all_directions() {
+        let matches = find_word(&get_grid(), &['C', 'A', 'T']);
+
+        assert_eq!(
+            matches
+                .iter()
+                .map(|word_match| (word_match.start, word_match.direction))
+                .collect::<Vec<_>>(),
+            vec![
+                (Point { x: 0, y: 0 }, Direction8Way::Right),
+                (Point { x: 0, y: 0 }, Direction8Way::Down),
+                (Point { x: 3, y: 2 }, Direction8Way::Up),
+            ]
+        );
+        assert_eq!(
+            matches[2].cells,
+            vec![
+                Point { x: 3, y: 2 },
+                Point { x: 3, y: 1 },
+                Point { x: 3, y: 0 }
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_words_with_any_alphabet() {
+        let grid = Grid::make(vec![vec![1, 2, 3], vec![3, 2, 1]]);
+        let found = find_words(&grid, &[&[1, 2, 3], &[2, 2], &[7]]);
+
+        assert_eq!(found.iter().filter(|(index, _)| *index == 0).count(), 2);
+        assert_eq!(found.iter().filter(|(index, _)| *index == 1).count(), 2);
+        assert!(found.iter().all(|(index, _)| *index != 2));
+        assert!(find_word(&grid, &[]).is_empty());
+
+        let single_letters = find_word(&grid, &[2]);
+        assert_eq!(single_letters.len(), 2);
+        assert!(single_letters
+            .iter()
+            .all(|word_match| word_match.direction == Direction8Way::Up));
+        assert!(find_word(&Grid::make(Vec::<Vec<u8>>::new()), &[1]).is_empty());
+    }
+
+    #[test]
+    fn test_pattern_rotations() {
+        let pattern = Pattern::parse("A.\n.B", '.');
+
+        assert_eq!(pattern.rotations().len(), 4);
+        assert_eq!(Pattern::parse("A.\n.A", '.').rotations().len(), 2);
+        assert_eq!(Pattern::parse("A.\n..", '.').rotations().len(), 4);
+        assert_eq!(
+            pattern.rotate_cw(),
+            Pattern::new(vec![
+                (Point { x: 1, y: 0 }, 'A'),
+                (Point { x: 0, y: 1 }, 'B')
+            ])
+        );
+        assert_eq!(
+            Pattern::parse("A\nC", '.').find(&get_grid()),
+            vec![Point { x: 3, y: 1 }]
+        );
+    }
+}